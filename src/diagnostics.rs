@@ -0,0 +1,44 @@
+use crate::wyrand::WyRand;
+
+/// Draw `samples` values in `[0, buckets)`, bin them, and return the
+/// chi-squared statistic of the histogram against a uniform distribution.
+///
+/// The statistic has `buckets - 1` degrees of freedom. For a healthy RNG
+/// it should sit near `buckets - 1`; values far above the critical value
+/// for that dof indicate the output is not uniform.
+pub fn chi_squared_uniformity(rng: &mut WyRand, buckets: usize, samples: usize) -> f64 {
+    assert!(buckets > 0, "chi_squared_uniformity requires at least one bucket");
+
+    let mut counts = vec![0u64; buckets];
+    for _ in 0..samples {
+        counts[rng.next_in_range(0..buckets)] += 1;
+    }
+
+    let expected = samples as f64 / buckets as f64;
+    counts
+        .iter()
+        .map(|&observed| {
+            let d = observed as f64 - expected;
+            d * d / expected
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wyrand_passes_uniformity() {
+        let mut rng = WyRand::with_seed(0);
+        // critical value for 99 degrees of freedom at p = 0.001
+        let stat = chi_squared_uniformity(&mut rng, 100, 100_000);
+        assert!(stat < 148.2, "chi-squared statistic {stat} too large");
+    }
+
+    #[test]
+    fn single_bucket_is_zero() {
+        let mut rng = WyRand::with_seed(0);
+        assert_eq!(chi_squared_uniformity(&mut rng, 1, 1000), 0.0);
+    }
+}
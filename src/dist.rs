@@ -0,0 +1,172 @@
+//! Distributions that keep precomputed state between samples.
+
+use crate::wyrand::WyRand;
+use crate::error::RngError;
+
+/// A Zipf (power-law) distribution over the ranks `1..=n`.
+///
+/// The probability of rank `k` is proportional to `1 / k^exponent`, so
+/// low ranks are sampled far more often than high ranks. Useful for
+/// simulating realistic access patterns, where a few items are popular.
+#[derive(Copy, Clone, Debug)]
+pub struct Zipf {
+    n: u64,
+    exponent: f64,
+    /// Normalization of the hat function's inverse CDF.
+    t: f64,
+    /// `1 / (1 - exponent)`, or 0 when the exponent is 1.
+    q: f64,
+}
+
+impl Zipf {
+    /// Construct a Zipf distribution over `1..=n`.
+    ///
+    /// Panics if `n` is 0 or `exponent` is negative or NaN.
+    pub fn new(n: u64, exponent: f64) -> Self {
+        assert!(n > 0, "Zipf requires n >= 1");
+        assert!(exponent >= 0.0, "Zipf requires a non-negative exponent");
+
+        let nf = n as f64;
+        let (t, q) = if exponent != 1.0 {
+            let q = 1.0 / (1.0 - exponent);
+            ((nf.powf(1.0 - exponent) - exponent) * q, q)
+        } else {
+            (1.0 + nf.ln(), 0.0)
+        };
+        Self { n, exponent, t, q }
+    }
+
+    /// The number of ranks.
+    pub fn n(&self) -> u64 {
+        self.n
+    }
+
+    /// The power-law exponent.
+    pub fn exponent(&self) -> f64 {
+        self.exponent
+    }
+
+    /// Sample a rank in `1..=n`.
+    ///
+    /// Uses rejection-inversion sampling, so the cost does not depend on `n`.
+    /// Panics if the rng's rejection cap is reached, see `try_sample`.
+    pub fn sample(&self, rng: &mut WyRand) -> u64 {
+        self.try_sample(rng).expect("Zipf sampling exceeded the rejection cap")
+    }
+
+    /// Sample a rank in `1..=n`, returning an error
+    /// if the rng's rejection cap is reached.
+    pub fn try_sample(&self, rng: &mut WyRand) -> Result<u64, RngError> {
+        rng.retry(|rng| {
+            let inv_b = self.inv_cdf(rng.next::<f64>());
+            let x = (inv_b + 1.0).floor();
+            let mut ratio = x.powf(-self.exponent);
+            if x > 1.0 {
+                ratio *= inv_b.powf(self.exponent);
+            }
+            (rng.next::<f64>() < ratio).then(|| (x as u64).clamp(1, self.n))
+        })
+    }
+
+    /// Inverse CDF of the hat function that dominates the distribution.
+    fn inv_cdf(&self, p: f64) -> f64 {
+        let pt = p * self.t;
+        if pt <= 1.0 {
+            pt
+        } else if self.exponent != 1.0 {
+            (pt * (1.0 - self.exponent) + self.exponent).powf(self.q)
+        } else {
+            (pt - 1.0).exp()
+        }
+    }
+}
+
+/// An empirical distribution built from observed data, for resampling
+/// new values consistent with measurements.
+#[derive(Clone, Debug)]
+pub struct Empirical {
+    /// The observed data, sorted ascending.
+    sorted: Vec<f64>,
+    interpolate: bool,
+}
+
+impl Empirical {
+    /// Construct a distribution that returns one of the observed values,
+    /// each equally likely (a bootstrap resample).
+    ///
+    /// Panics if `data` is empty or contains NaN.
+    pub fn from_samples(data: &[f64]) -> Self {
+        assert!(!data.is_empty(), "Empirical requires at least one sample");
+        assert!(!data.iter().any(|v| v.is_nan()), "Empirical samples must not be NaN");
+        let mut sorted = data.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        Self { sorted, interpolate: false }
+    }
+
+    /// Construct a distribution that interpolates linearly between the sorted
+    /// observed values, so samples are continuous between the min and max.
+    ///
+    /// Panics if `data` is empty or contains NaN.
+    pub fn from_samples_interpolated(data: &[f64]) -> Self {
+        Self { interpolate: true, ..Self::from_samples(data) }
+    }
+
+    /// Draw a value from the distribution.
+    pub fn sample(&self, rng: &mut WyRand) -> f64 {
+        let last = self.sorted.len() - 1;
+        if !self.interpolate || last == 0 {
+            return self.sorted[rng.next_in_range(0..self.sorted.len())];
+        }
+        // invert the piecewise linear CDF through the sorted points
+        let pos = rng.next_f64_full() * last as f64;
+        let i = pos as usize;
+        let t = pos - i as f64;
+        self.sorted[i] * (1.0 - t) + self.sorted[i + 1] * t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zipf_rank_one_dominates() {
+        let zipf = Zipf::new(100, 1.5);
+        let mut rng = WyRand::with_seed(0);
+        let mut counts = vec![0u32; 101];
+        for _ in 0..100_000 {
+            let k = zipf.sample(&mut rng);
+            assert!((1..=100).contains(&k));
+            counts[k as usize] += 1;
+        }
+        assert!(counts[1] > counts[2] && counts[2] > counts[4]);
+        // count(1) / count(k) should be about k^exponent
+        let exponent = (counts[1] as f64 / counts[4] as f64).ln() / 4f64.ln();
+        assert!((exponent - 1.5).abs() < 0.1, "empirical exponent {exponent}");
+    }
+
+    #[test]
+    fn empirical_matches_data_moments() {
+        let data = [5.0, 1.0, 4.0, 2.0, 3.0];
+        let mut rng = WyRand::with_seed(0);
+        let moments = |dist: &Empirical, rng: &mut WyRand| {
+            let xs: Vec<f64> = (0..100_000).map(|_| dist.sample(rng)).collect();
+            let mean = xs.iter().sum::<f64>() / xs.len() as f64;
+            let var = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / xs.len() as f64;
+            (xs, mean, var)
+        };
+
+        let (xs, mean, var) = moments(&Empirical::from_samples(&data), &mut rng);
+        assert!(xs.iter().all(|x| data.contains(x)));
+        assert!((mean - 3.0).abs() < 0.02, "{mean}");
+        assert!((var - 2.0).abs() < 0.05, "{var}");
+
+        // evenly spaced points interpolate to a uniform distribution on [1, 5]
+        let (xs, mean, var) = moments(&Empirical::from_samples_interpolated(&data), &mut rng);
+        assert!(xs.iter().all(|x| (1.0..=5.0).contains(x)));
+        assert!((mean - 3.0).abs() < 0.02, "{mean}");
+        assert!((var - 16.0 / 12.0).abs() < 0.05, "{var}");
+
+        assert_eq!(Empirical::from_samples_interpolated(&[7.0]).sample(&mut rng), 7.0);
+    }
+}
@@ -0,0 +1,42 @@
+use std::fmt;
+
+/// Errors produced by fallible generation methods.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RngError {
+    /// A rejection sampling loop exceeded the rng's rejection cap
+    /// without producing an accepted value.
+    TooManyRejections,
+}
+
+impl fmt::Display for RngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RngError::TooManyRejections => write!(f, "rejection sampling exceeded the rejection cap"),
+        }
+    }
+}
+
+impl std::error::Error for RngError {}
+
+/// Errors produced when decoding a Permutation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PermError {
+    /// The input contains a character that isn't valid base64, or bad padding.
+    InvalidBase64,
+    /// The input decoded to this many bytes instead of 256.
+    InvalidLength(usize),
+    /// The decoded bytes are not a permutation of 0..256.
+    NotAPermutation,
+}
+
+impl fmt::Display for PermError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermError::InvalidBase64 => write!(f, "invalid base64"),
+            PermError::InvalidLength(len) => write!(f, "expected 256 bytes, got {len}"),
+            PermError::NotAPermutation => write!(f, "bytes are not a permutation of 0..256"),
+        }
+    }
+}
+
+impl std::error::Error for PermError {}
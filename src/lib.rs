@@ -53,8 +53,10 @@
 //! (and seed) is the same. 
 //! 
 //! ```
+//! # #[cfg(feature = "glam")]
 //! use glam::IVec3;
 //! 
+//! # #[cfg(feature = "glam")]
 //! fn main() {
 //!     // instantiate the Permutation with thread-local seed
 //!     let mut rng = justrng::Permutation::new();
@@ -70,18 +72,39 @@
 //!     let v2 = rng.mix(vec);
 //!     assert_eq!(v1, v2);
 //! }
+//! # #[cfg(not(feature = "glam"))]
+//! # fn main() {}
 //! ```
 
+#![allow(clippy::needless_doctest_main)]
+
 pub mod seed;
 pub mod perm;
 pub mod wyrand;
+pub mod wyrand32;
+pub mod counter;
 pub mod primes;
+pub mod diagnostics;
+pub mod noise;
+pub mod dist;
+pub mod quasi;
+pub mod error;
+pub mod shared;
+pub mod maze;
+pub mod sample;
+#[cfg(feature = "testdata")]
+pub mod testdata;
+#[cfg(feature = "markov")]
+pub mod markov;
 
-pub use wyrand::WyRand;
+pub use wyrand::{WyRand, StickyBool};
+pub use wyrand32::WyRand32;
+pub use counter::Counter;
 pub use perm::Permutation;
+pub use error::{RngError, PermError};
+pub use shared::SharedRng;
 
-use wyrand::{FromRng, RangeRng};
-use std::ops::Range;
+use wyrand::{FromRng, SampleRange};
 
 /// Generate a random number
 pub fn next<T: FromRng>() -> T {
@@ -89,8 +112,8 @@ pub fn next<T: FromRng>() -> T {
 }
 
 /// Generate a random number within a range.
-pub fn next_in_range<T: RangeRng>(range: Range<T>) -> T {
-    T::from_range(crate::seed::from_local(), range)
+pub fn next_in_range<T>(range: impl SampleRange<T>) -> T {
+    range.sample(crate::seed::from_local())
 }
 
 /// Get an RNG seeded from system source.
@@ -0,0 +1,85 @@
+//! Procedural name generation with character Markov chains,
+//! enabled with the `markov` feature.
+
+use std::collections::HashMap;
+use crate::wyrand::WyRand;
+
+/// Marks the start and end of a name in the transition table.
+const BOUNDARY: char = '\0';
+
+/// Generates names that resemble a training corpus, using order-2
+/// character transitions: each character is chosen based on the two
+/// characters before it, weighted by how often that sequence appears.
+#[derive(Clone, Debug)]
+pub struct NameGenerator {
+    /// For each pair of preceding characters, the characters that
+    /// followed them in the corpus and how many times.
+    transitions: HashMap<(char, char), Vec<(char, u32)>>,
+    max_len: usize,
+}
+
+impl NameGenerator {
+    /// Build the transition table from a corpus of names.
+    pub fn new(corpus: &[&str]) -> Self {
+        let mut transitions: HashMap<(char, char), Vec<(char, u32)>> = HashMap::new();
+        let mut max_len = 0;
+        for name in corpus {
+            let mut prev = (BOUNDARY, BOUNDARY);
+            for c in name.chars().chain([BOUNDARY]) {
+                let next = transitions.entry(prev).or_default();
+                match next.iter_mut().find(|(n, _)| *n == c) {
+                    Some((_, count)) => *count += 1,
+                    None => next.push((c, 1)),
+                }
+                prev = (prev.1, c);
+            }
+            max_len = max_len.max(name.chars().count());
+        }
+        Self { transitions, max_len }
+    }
+
+    /// Generate a name. Names are never longer than the longest name
+    /// in the corpus, and only use characters seen in the corpus.
+    pub fn generate(&self, rng: &mut WyRand) -> String {
+        let mut name = String::new();
+        let mut prev = (BOUNDARY, BOUNDARY);
+        for _ in 0..self.max_len {
+            let Some(next) = self.transitions.get(&prev) else { break };
+            let total: u32 = next.iter().map(|(_, n)| n).sum();
+            let mut k = rng.next_in_range(0..total);
+            let &(c, _) = next
+                .iter()
+                .find(|(_, n)| match k.checked_sub(*n) {
+                    Some(rest) => { k = rest; false }
+                    None => true,
+                })
+                .expect("k is less than the total count");
+            if c == BOUNDARY {
+                break;
+            }
+            name.push(c);
+            prev = (prev.1, c);
+        }
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_use_corpus_characters_and_are_deterministic() {
+        let corpus = ["aldric", "brenna", "corwin", "delia", "edric", "fenna"];
+        let generator = NameGenerator::new(&corpus);
+        let charset: String = corpus.concat();
+        let mut a = WyRand::with_seed(0);
+        let mut b = WyRand::with_seed(0);
+        for _ in 0..1_000 {
+            let name = generator.generate(&mut a);
+            assert!(name.chars().count() <= 6);
+            assert!(name.chars().all(|c| charset.contains(c)), "{name}");
+            assert_eq!(name, generator.generate(&mut b));
+        }
+    }
+}
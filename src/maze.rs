@@ -0,0 +1,89 @@
+//! Maze generation.
+
+use crate::wyrand::WyRand;
+
+impl WyRand {
+    /// Carve a perfect maze (every open cell reachable, no loops) with a
+    /// randomized depth-first search.
+    ///
+    /// Returns a row-major `width x height` grid where `true` is a wall.
+    /// Open cells sit at odd coordinates, and the passages between them at
+    /// the cells in between, so the outer border is always wall. Use odd
+    /// dimensions to avoid a double-thick wall on the right or bottom edge.
+    /// Grids smaller than 3x3 have no room for a passage and are all wall.
+    pub fn carve_maze(&mut self, width: usize, height: usize) -> Vec<bool> {
+        let len = width
+            .checked_mul(height)
+            .expect("carve_maze dimensions overflow usize");
+        let mut walls = vec![true; len];
+        if width < 3 || height < 3 {
+            return walls;
+        }
+
+        let mut stack = vec![(1, 1)];
+        walls[width + 1] = false;
+        while let Some(&(x, y)) = stack.last() {
+            let mut neighbors = [(0, 0); 4];
+            let mut count = 0;
+            for (dx, dy) in [(2isize, 0isize), (-2, 0), (0, 2), (0, -2)] {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                if nx > 0 && ny > 0 && (nx as usize) < width - 1 && (ny as usize) < height - 1 {
+                    let (nx, ny) = (nx as usize, ny as usize);
+                    if walls[ny * width + nx] {
+                        neighbors[count] = (nx, ny);
+                        count += 1;
+                    }
+                }
+            }
+
+            if count == 0 {
+                stack.pop();
+                continue;
+            }
+
+            let (nx, ny) = neighbors[self.next_in_range(0..count)];
+            // open the passage between the cells, then the new cell
+            walls[(y + ny) / 2 * width + (x + nx) / 2] = false;
+            walls[ny * width + nx] = false;
+            stack.push((nx, ny));
+        }
+        walls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maze_is_a_spanning_tree() {
+        let mut rng = WyRand::with_seed(0);
+        let (width, height) = (31, 21);
+        let walls = rng.carve_maze(width, height);
+        assert_eq!(walls.len(), width * height);
+
+        let open: Vec<usize> = (0..walls.len()).filter(|&i| !walls[i]).collect();
+        // every cell at odd coordinates is open
+        assert_eq!(open.iter().filter(|&&i| i % width % 2 == 1 && i / width % 2 == 1).count(), 15 * 10);
+
+        // a connected graph with one less edge than it has nodes is a tree
+        let edges = open
+            .iter()
+            .map(|&i| (i + 1 < walls.len() && !walls[i + 1]) as usize + (i + width < walls.len() && !walls[i + width]) as usize)
+            .sum::<usize>();
+        assert_eq!(edges, open.len() - 1);
+
+        let mut seen = vec![false; walls.len()];
+        let mut stack = vec![width + 1];
+        seen[width + 1] = true;
+        while let Some(i) = stack.pop() {
+            for j in [i - 1, i + 1, i - width, i + width] {
+                if !walls[j] && !seen[j] {
+                    seen[j] = true;
+                    stack.push(j);
+                }
+            }
+        }
+        assert!(open.iter().all(|&i| seen[i]));
+    }
+}
@@ -0,0 +1,431 @@
+use crate::perm::Permutation;
+#[cfg(feature = "glam")]
+use glam::Vec2;
+
+/// The standard 12 gradients for 3D Perlin noise,
+/// pointing at the edge midpoints of a cube.
+pub const GRADIENTS3: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0], [1.0, 0.0, -1.0], [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0], [0.0, -1.0, 1.0], [0.0, 1.0, -1.0], [0.0, -1.0, -1.0],
+];
+
+impl Permutation {
+    /// Smooth 1D gradient noise, roughly in the range [-1, 1].
+    /// 
+    /// The quintic fade makes the output and its first two derivatives
+    /// continuous across integer boundaries, so there are no audible
+    /// clicks when it drives audio, e.g. as a random LFO.
+    pub fn smooth1(&self, t: f32) -> f32 {
+        let tf = t.floor();
+        let i = tf as i32;
+        let x = t - tf;
+        let g0 = self.mix(i) as f32 / 127.5 - 1.0;
+        let g1 = self.mix(i.wrapping_add(1)) as f32 / 127.5 - 1.0;
+        2.0 * lerp(g0 * x, g1 * (x - 1.0), fade(x))
+    }
+
+    /// Capture a frequency and amplitude as a `NoiseLayer`, for composing
+    /// terrain from several layers of noise.
+    pub fn noise_layer(&self, freq: f32, amp: f32) -> NoiseLayer<'_> {
+        NoiseLayer { perm: self, freq, amp }
+    }
+
+    /// 2D Perlin noise. Output is roughly in the range [-1, 1],
+    /// and is always 0 at integer coordinates.
+    pub fn perlin2(&self, x: f32, y: f32) -> f32 {
+        let p = self.as_bytes_padded();
+        let (xf, yf) = (x.floor(), y.floor());
+        let xi = (xf as i32 & 255) as usize;
+        let yi = (yf as i32 & 255) as usize;
+        let (x, y) = (x - xf, y - yf);
+        let (u, v) = (fade(x), fade(y));
+
+        let a = p[xi] as usize;
+        let b = p[xi + 1] as usize;
+        let aa = p[a + yi];
+        let ab = p[a + yi + 1];
+        let ba = p[b + yi];
+        let bb = p[b + yi + 1];
+
+        lerp(
+            lerp(grad2(aa, x, y), grad2(ba, x - 1.0, y), u),
+            lerp(grad2(ab, x, y - 1.0), grad2(bb, x - 1.0, y - 1.0), u),
+            v,
+        )
+    }
+
+    /// 3D Perlin noise using the permutation's gradient set.
+    /// Output is roughly in the range [-1, 1] for the default gradients.
+    pub fn perlin3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let p = self.as_bytes_padded();
+        let g = self.gradient_set3();
+        let (xf, yf, zf) = (x.floor(), y.floor(), z.floor());
+        let xi = (xf as i32 & 255) as usize;
+        let yi = (yf as i32 & 255) as usize;
+        let zi = (zf as i32 & 255) as usize;
+        let (x, y, z) = (x - xf, y - yf, z - zf);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let a = p[xi] as usize + yi;
+        let aa = p[a] as usize + zi;
+        let ab = p[a + 1] as usize + zi;
+        let b = p[xi + 1] as usize + yi;
+        let ba = p[b] as usize + zi;
+        let bb = p[b + 1] as usize + zi;
+
+        lerp(
+            lerp(
+                lerp(grad3(g, p[aa], x, y, z), grad3(g, p[ba], x - 1.0, y, z), u),
+                lerp(grad3(g, p[ab], x, y - 1.0, z), grad3(g, p[bb], x - 1.0, y - 1.0, z), u),
+                v,
+            ),
+            lerp(
+                lerp(grad3(g, p[aa + 1], x, y, z - 1.0), grad3(g, p[ba + 1], x - 1.0, y, z - 1.0), u),
+                lerp(grad3(g, p[ab + 1], x, y - 1.0, z - 1.0), grad3(g, p[bb + 1], x - 1.0, y - 1.0, z - 1.0), u),
+                v,
+            ),
+            w,
+        )
+    }
+
+    /// A unit vector derived from the hashed cell, for building flow fields.
+    /// The angle is quantized to 256 directions.
+    #[cfg(feature = "glam")]
+    pub fn flow_direction(&self, x: i32, y: i32) -> Vec2 {
+        let angle = self.mix((x, y)) as f32 * (std::f32::consts::TAU / 256.0);
+        Vec2::from_angle(angle)
+    }
+
+    /// 2D Worley (cellular) noise. Each integer cell holds one feature point,
+    /// placed by hashing the cell. Returns the distance from `(x, y)` to the
+    /// nearest feature point, in the range [0, sqrt(2)].
+    pub fn worley2(&self, x: f32, y: f32) -> f32 {
+        self.worley2_f1f2(x, y).0
+    }
+
+    /// 2D Worley noise returning the distances to the nearest (F1) and
+    /// second nearest (F2) feature points, with `F2 >= F1 >= 0`.
+    /// `F2 - F1` is near 0 at the edges between cells.
+    pub fn worley2_f1f2(&self, x: f32, y: f32) -> (f32, f32) {
+        let (cx, cy) = (x.floor() as i32, y.floor() as i32);
+        let (mut f1, mut f2) = (f32::MAX, f32::MAX);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (px, py) = self.feature_point2(cx + dx, cy + dy);
+                let d = (px - x).hypot(py - y);
+                if d < f1 {
+                    f2 = f1;
+                    f1 = d;
+                } else if d < f2 {
+                    f2 = d;
+                }
+            }
+        }
+        (f1, f2)
+    }
+
+    /// Fractal Worley noise, layering `octaves` of `worley2` like `fbm2`.
+    /// The octaves are averaged by amplitude, so like `worley2`
+    /// the output is in the range [0, sqrt(2)].
+    pub fn worley_fbm2(&self, x: f32, y: f32, octaves: u32, lacunarity: f32, persistence: f32) -> f32 {
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        let mut freq = 1.0;
+        let mut amp = 1.0;
+        for _ in 0..octaves {
+            sum += self.worley2(x * freq, y * freq) * amp;
+            total += amp;
+            freq *= lacunarity;
+            amp *= persistence;
+        }
+        if total == 0.0 { 0.0 } else { sum / total }
+    }
+
+    /// The position of the feature point in a Worley noise cell.
+    fn feature_point2(&self, cx: i32, cy: i32) -> (f32, f32) {
+        let h = self.mix((cx, cy));
+        let h2 = self.mix((cx, cy, 1));
+        (cx as f32 + (h as f32 + 0.5) / 256.0, cy as f32 + (h2 as f32 + 0.5) / 256.0)
+    }
+
+    /// Sample a `dims[0] x dims[1]` grid of 2D Perlin noise in row-major order,
+    /// where cell `(i, j)` is sampled at `origin + (i, j) * step`.
+    ///
+    /// A grid with origin `origin + dims * step` continues exactly where this one
+    /// ends, so tiles generated this way line up seamlessly.
+    pub fn sample_grid(&self, origin: [f32; 2], step: [f32; 2], dims: [usize; 2]) -> Vec<f32> {
+        let len = dims[0]
+            .checked_mul(dims[1])
+            .expect("sample_grid dimensions overflow usize");
+
+        let mut result = Vec::with_capacity(len);
+        for j in 0..dims[1] {
+            let y = origin[1] + j as f32 * step[1];
+            for i in 0..dims[0] {
+                result.push(self.perlin2(origin[0] + i as f32 * step[0], y));
+            }
+        }
+        debug_assert_eq!(result.len(), len);
+        result
+    }
+
+    /// Fractal brownian motion, layering `octaves` of 2D Perlin noise.
+    ///
+    /// Each octave multiplies the frequency by `lacunarity` and the amplitude
+    /// by `persistence`. The sum is divided by the total amplitude, so the
+    /// output stays roughly in the range [-1, 1].
+    pub fn fbm2(&self, x: f32, y: f32, octaves: u32, lacunarity: f32, persistence: f32) -> f32 {
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        let mut freq = 1.0;
+        let mut amp = 1.0;
+        for _ in 0..octaves {
+            sum += self.perlin2(x * freq, y * freq) * amp;
+            total += amp;
+            freq *= lacunarity;
+            amp *= persistence;
+        }
+        if total == 0.0 { 0.0 } else { sum / total }
+    }
+
+    /// Fill a row-major `width x height` buffer with fbm noise, where
+    /// cell `(x, y)` is sampled at `(x * scale, y * scale)`.
+    ///
+    /// Panics if `buf.len()` is not `width * height`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_fbm2(
+        &self,
+        buf: &mut [f32],
+        width: usize,
+        height: usize,
+        scale: f32,
+        octaves: u32,
+        lacunarity: f32,
+        persistence: f32,
+    ) {
+        assert_eq!(
+            Some(buf.len()),
+            width.checked_mul(height),
+            "fill_fbm2 buffer length must equal width * height"
+        );
+        if width == 0 {
+            return;
+        }
+        for (i, v) in buf.iter_mut().enumerate() {
+            let x = (i % width) as f32 * scale;
+            let y = (i / width) as f32 * scale;
+            *v = self.fbm2(x, y, octaves, lacunarity, persistence);
+        }
+    }
+}
+
+/// A layer of Perlin noise with a fixed frequency and amplitude,
+/// created with `Permutation::noise_layer`.
+#[derive(Copy, Clone)]
+pub struct NoiseLayer<'a> {
+    perm: &'a Permutation,
+    freq: f32,
+    amp: f32,
+}
+
+impl NoiseLayer<'_> {
+    /// Sample the layer, `perlin2(x * freq, y * freq) * amp`.
+    pub fn sample2(&self, x: f32, y: f32) -> f32 {
+        self.perm.perlin2(x * self.freq, y * self.freq) * self.amp
+    }
+}
+
+/// Sum the samples of several noise layers at the same point.
+pub fn sum2(layers: &[NoiseLayer], x: f32, y: f32) -> f32 {
+    layers.iter().map(|layer| layer.sample2(x, y)).sum()
+}
+
+/// Quintic fade curve, 6t^5 - 15t^4 + 10t^3.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + t * (b - a)
+}
+
+/// Dot product of the offset with one of 8 gradients selected by the hash.
+fn grad2(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+/// Dot product of the offset with the gradient selected by the hash.
+fn grad3(gradients: &[[f32; 3]], hash: u8, x: f32, y: f32, z: f32) -> f32 {
+    let [gx, gy, gz] = gradients[hash as usize % gradients.len()];
+    gx * x + gy * y + gz * z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_grid_tiles_continue_across_edges() {
+        let perm = Permutation::with_seed(0);
+        let step = [0.25, 0.25];
+        let whole = perm.sample_grid([0.0, 0.0], step, [16, 4]);
+        let left = perm.sample_grid([0.0, 0.0], step, [8, 4]);
+        let right = perm.sample_grid([2.0, 0.0], step, [8, 4]);
+        assert_eq!(whole.len(), 64);
+        for j in 0..4 {
+            assert_eq!(&whole[j * 16..j * 16 + 8], &left[j * 8..j * 8 + 8]);
+            assert_eq!(&whole[j * 16 + 8..j * 16 + 16], &right[j * 8..j * 8 + 8]);
+            // neighbours across the seam are a quarter cell apart, so close in value
+            assert!((left[j * 8 + 7] - right[j * 8]).abs() < 0.5);
+        }
+    }
+
+    #[test]
+    fn fill_fbm2_is_deterministic_and_uses_octaves() {
+        let perm = Permutation::with_seed(0);
+        let (mut a, mut b, mut c) = (vec![0.0; 64], vec![0.0; 64], vec![0.0; 64]);
+        perm.fill_fbm2(&mut a, 8, 8, 0.37, 4, 2.0, 0.5);
+        perm.fill_fbm2(&mut b, 8, 8, 0.37, 4, 2.0, 0.5);
+        perm.fill_fbm2(&mut c, 8, 8, 0.37, 1, 2.0, 0.5);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a[3 * 8 + 5], perm.fbm2(5.0 * 0.37, 3.0 * 0.37, 4, 2.0, 0.5));
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn custom_gradient_set_changes_perlin3() {
+        use glam::Vec3;
+        let default = Permutation::with_seed(0);
+        let mut perm = Permutation::with_seed(0);
+        let points = [(0.3, 1.7, 2.2), (5.5, 0.25, 9.1), (-3.4, 2.0, 0.6)];
+
+        let builtin: Vec<Vec3> = GRADIENTS3.iter().map(|g| Vec3::from_array(*g)).collect();
+        perm.set_gradient_set3(&builtin);
+        for (x, y, z) in points {
+            assert_eq!(perm.perlin3(x, y, z), default.perlin3(x, y, z));
+        }
+
+        perm.set_gradient_set3(&[Vec3::X, Vec3::Y]);
+        assert!(points.iter().any(|&(x, y, z)| perm.perlin3(x, y, z) != default.perlin3(x, y, z)));
+
+        perm.reset_gradient_set3();
+        assert_eq!(perm.gradient_set3(), &GRADIENTS3[..]);
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn flow_direction_is_normalized_and_deterministic() {
+        let perm = Permutation::with_seed(0);
+        let other = Permutation::with_seed(0);
+        for x in -10..10 {
+            for y in -10..10 {
+                let d = perm.flow_direction(x, y);
+                assert!((d.length() - 1.0).abs() < 1e-5);
+                assert_eq!(d, other.flow_direction(x, y));
+            }
+        }
+
+        // neighbouring cells generally point in different directions
+        let mut pairs = 0;
+        let mut differ = 0;
+        for x in -10..10 {
+            for y in -10..10 {
+                for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                    pairs += 1;
+                    differ += (perm.flow_direction(x, y) != perm.flow_direction(nx, ny)) as u32;
+                }
+            }
+        }
+        assert!(differ as f64 > pairs as f64 * 0.95, "{differ} of {pairs}");
+    }
+
+    #[test]
+    fn worley2_is_deterministic_and_bounded() {
+        let perm = Permutation::with_seed(0);
+        let other = Permutation::with_seed(0);
+        for i in 0..400 {
+            let (x, y) = (i as f32 * 0.37 - 50.0, i as f32 * 0.91 - 80.0);
+            let d = perm.worley2(x, y);
+            assert!((0.0..=std::f32::consts::SQRT_2).contains(&d), "distance {d}");
+            assert_eq!(d, other.worley2(x, y));
+        }
+
+        // each cell places its feature point at its own offset
+        let mut offsets = Vec::new();
+        for cy in -5..5 {
+            for cx in -5..5 {
+                let (px, py) = perm.feature_point2(cx, cy);
+                let offset = (px - cx as f32, py - cy as f32);
+                assert!((0.0..1.0).contains(&offset.0) && (0.0..1.0).contains(&offset.1));
+                offsets.push(offset);
+            }
+        }
+        let distinct = offsets.iter().enumerate().filter(|(i, o)| !offsets[..*i].contains(o)).count();
+        assert!(distinct >= 95, "only {distinct} distinct offsets");
+    }
+
+    #[test]
+    fn worley2_f2_is_at_least_f1() {
+        let perm = Permutation::with_seed(0);
+        for i in 0..400 {
+            let (x, y) = (i as f32 * 0.53 - 30.0, i as f32 * 0.29 + 11.0);
+            let (f1, f2) = perm.worley2_f1f2(x, y);
+            assert!(f2 >= f1 && f1 >= 0.0, "f1 {f1}, f2 {f2}");
+            assert_eq!(f1, perm.worley2(x, y));
+        }
+    }
+
+    #[test]
+    fn worley_fbm2_stays_in_range() {
+        let perm = Permutation::with_seed(0);
+        for j in 0..30 {
+            for i in 0..30 {
+                let v = perm.worley_fbm2(i as f32 * 0.31 - 4.0, j as f32 * 0.27 + 2.0, 4, 2.0, 0.5);
+                assert!((0.0..=std::f32::consts::SQRT_2).contains(&v), "{v}");
+            }
+        }
+        assert_eq!(perm.worley_fbm2(1.5, 2.5, 0, 2.0, 0.5), 0.0);
+    }
+
+    #[test]
+    fn smooth1_is_continuous_at_integers() {
+        let perm = Permutation::with_seed(0);
+        for i in -20..20 {
+            let t = i as f32;
+            let (below, at, above) = (perm.smooth1(t - 1e-3), perm.smooth1(t), perm.smooth1(t + 1e-3));
+            assert_eq!(at, 0.0);
+            assert!(below.abs() < 1e-2 && above.abs() < 1e-2, "jump at {t}: {below} {above}");
+
+            // the one-sided finite differences agree, so the slope doesn't jump either
+            let (lo, hi) = (t - 1e-3, t + 1e-3);
+            let left = (at - perm.smooth1(lo)) / (t - lo);
+            let right = (perm.smooth1(hi) - at) / (hi - t);
+            assert!((left - right).abs() < 2e-2, "slope jump at {t}: {left} {right}");
+        }
+    }
+
+    #[test]
+    fn noise_layer_scales_perlin2() {
+        let perm = Permutation::with_seed(0);
+        let low = perm.noise_layer(0.5, 2.0);
+        let high = perm.noise_layer(4.0, 0.25);
+        for i in 0..50 {
+            let (x, y) = (i as f32 * 0.37, i as f32 * 0.61);
+            assert_eq!(low.sample2(x, y), perm.perlin2(x * 0.5, y * 0.5) * 2.0);
+            assert_eq!(high.sample2(x, y), perm.perlin2(x * 4.0, y * 4.0) * 0.25);
+            assert_eq!(sum2(&[low, high], x, y), low.sample2(x, y) + high.sample2(x, y));
+        }
+        assert_eq!(sum2(&[], 1.5, 2.5), 0.0);
+    }
+}
@@ -1,6 +1,8 @@
 
+use std::ops::Index;
+use crate::error::PermError;
 #[cfg(feature = "glam")]
-use glam::{IVec2, IVec3, IVec4, UVec2, UVec3, UVec4};
+use glam::{IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, Vec3};
 
 /// A permutation RNG. Indexes into a table instead of hashing a state
 /// to "mix" values. Primarily used in procedural texture generation to
@@ -10,7 +12,13 @@ use glam::{IVec2, IVec3, IVec4, UVec2, UVec3, UVec4};
 /// 256 bytes. This is so we can index the permutation with the sum
 /// of a hash byte and a permutation byte without wrapping.
 #[derive(Clone)]
-pub struct Permutation([u8; 512]);
+pub struct Permutation {
+    table: [u8; 512],
+    /// Custom gradient set for 3D noise, `None` uses the standard 12.
+    gradients3: Option<Box<[[f32; 3]]>>,
+    /// The seed the table was shuffled with, if it came from `with_seed`.
+    seed: Option<u64>,
+}
 
 impl Permutation {
     const DEFAULT: [u8; 512] = {
@@ -46,17 +54,57 @@ impl Permutation {
             .shuffle(&mut result[..256]);
         // copy lower 256 to upper 256
         result[..].copy_within(..256, 256);
-        Self(result)
+        Self {
+            seed: Some(seed),
+            ..Self::from_bytes_padded(result)
+        }
+    }
+
+    /// Construct a Permutation keyed by a string, like a world name,
+    /// using the seed from `seed::from_str`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Self {
+        Self::with_seed(crate::seed::from_str(s))
+    }
+
+    /// The seed this permutation was constructed with, or `None` if it was
+    /// constructed from bytes or derived from another permutation.
+    pub fn source_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Derive a new permutation from this one and a salt, by shuffling this
+    /// table with a seed hashed from its bytes and the salt. Useful for a
+    /// family of related but distinct noise layers from one base permutation.
+    pub fn derive(&self, salt: u64) -> Permutation {
+        let seed = self.as_bytes()
+            .chunks_exact(8)
+            .fold(salt, |h, chunk| {
+                crate::wyrand::mix2(h, u64::from_le_bytes(chunk.try_into().unwrap()))
+            });
+        let mut result = self.clone();
+        crate::wyrand::WyRand::with_seed(seed)
+            .shuffle(&mut result.table[..256]);
+        result.table.copy_within(..256, 256);
+        result.seed = None;
+        result
     }
 
     /// Hash a value, returning a u8 in the range [0,256).
     pub fn mix(&self, v: impl PermMix) -> u8 {
-        v.perm_mix(&self.0)
+        v.perm_mix(&self.table)
+    }
+
+    /// Hash a value with a salt, returning a u8 in the range [0,256).
+    /// Different salts give decorrelated channels from the same table,
+    /// without reshuffling it.
+    pub fn mix_salted(&self, v: impl PermMix, salt: u8) -> u8 {
+        self.table[v.perm_mix(&self.table) as usize + salt as usize]
     }
 
     /// Get a reference to the permutation bytes.
     pub fn as_bytes(&self) -> &[u8] {
-        &self.0[..256]
+        &self.table[..256]
     }
 
     /// Construct the permutation from 256 bytes.
@@ -64,20 +112,120 @@ impl Permutation {
         let mut result = [0; 512];
         result[..256].copy_from_slice(&bytes);
         result[256..].copy_from_slice(&bytes);
-        Self(result)
+        Self::from_bytes_padded(result)
     }
 
     /// Get a reference to the inner permutation bytes, with the padding.
     pub fn as_bytes_padded(&self) -> &[u8; 512] {
-        &self.0
+        &self.table
     }
 
     /// Construct the Permutation from bytes, with the padding.
     pub fn from_bytes_padded(bytes: [u8; 512]) -> Self {
-        Self(bytes)
+        Self {
+            table: bytes,
+            gradients3: None,
+            seed: None,
+        }
+    }
+
+    /// Encode the 256 byte table as a base64 string, for sharing presets.
+    pub fn to_base64(&self) -> String {
+        let mut result = String::with_capacity(344);
+        for chunk in self.as_bytes().chunks(3) {
+            let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+            let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    result.push(BASE64[(n >> (18 - 6 * i)) as usize & 63] as char);
+                } else {
+                    result.push('=');
+                }
+            }
+        }
+        result
+    }
+
+    /// Decode a permutation from a string produced by `to_base64`.
+    /// The decoded bytes must be a permutation of 0..256.
+    pub fn from_base64(s: &str) -> Result<Self, PermError> {
+        let s = s.as_bytes();
+        if !s.len().is_multiple_of(4) {
+            return Err(PermError::InvalidBase64);
+        }
+        let mut bytes = Vec::with_capacity(s.len() / 4 * 3);
+        for (i, quad) in s.chunks(4).enumerate() {
+            let is_last = i == s.len() / 4 - 1;
+            let pad = quad.iter().rev().take_while(|&&c| c == b'=').count();
+            if pad > 2 || (pad > 0 && !is_last) {
+                return Err(PermError::InvalidBase64);
+            }
+            let mut n = 0u32;
+            for &c in &quad[..4 - pad] {
+                let v = BASE64.iter().position(|&b| b == c).ok_or(PermError::InvalidBase64)?;
+                n = n << 6 | v as u32;
+            }
+            n <<= 6 * pad as u32;
+            // bits below the last full byte must be zero, so each table
+            // has exactly one encoding
+            if n & ((1 << (8 * pad)) - 1) != 0 {
+                return Err(PermError::InvalidBase64);
+            }
+            bytes.extend_from_slice(&n.to_be_bytes()[1..4 - pad]);
+        }
+
+        let bytes: [u8; 256] = bytes
+            .try_into()
+            .map_err(|b: Vec<u8>| PermError::InvalidLength(b.len()))?;
+        let mut seen = [false; 256];
+        for &b in &bytes {
+            if std::mem::replace(&mut seen[b as usize], true) {
+                return Err(PermError::NotAPermutation);
+            }
+        }
+        Ok(Self::from_bytes(bytes))
+    }
+
+    /// Replace the gradient vectors used by 3D noise. Hashed lattice
+    /// points select a gradient by `hash % gradients.len()`.
+    /// 
+    /// Panics if `gradients` is empty.
+    #[cfg(feature = "glam")]
+    pub fn set_gradient_set3(&mut self, gradients: &[Vec3]) {
+        assert!(!gradients.is_empty(), "gradient set must not be empty");
+        self.gradients3 = Some(gradients.iter().map(|g| g.to_array()).collect());
+    }
+
+    /// Restore the standard 12 gradients used by 3D noise.
+    pub fn reset_gradient_set3(&mut self) {
+        self.gradients3 = None;
+    }
+
+    /// The gradient vectors used by 3D noise.
+    pub fn gradient_set3(&self) -> &[[f32; 3]] {
+        self.gradients3.as_deref().unwrap_or(&crate::noise::GRADIENTS3)
+    }
+}
+
+/// Index into the 256 byte base table. Indices are masked to `i & 255`,
+/// so out of range indices wrap around instead of panicking.
+impl Index<usize> for Permutation {
+    type Output = u8;
+
+    fn index(&self, i: usize) -> &u8 {
+        &self.table[i & 255]
+    }
+}
+
+impl Default for Permutation {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+/// The standard base64 alphabet.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
 /// Mix behavior for a value in the permutation.
 pub trait PermMix {
     fn perm_mix(self, perm: &[u8; 512]) -> u8;
@@ -86,7 +234,7 @@ pub trait PermMix {
 #[cfg(feature = "glam")]
 impl PermMix for IVec2 {
     fn perm_mix(self, perm: &[u8; 512]) -> u8 {
-        perm[(self.x & 255) as usize + perm[(self.y & 255) as usize] as usize]
+        (self.x, self.y).perm_mix(perm)
     }
 }
 
@@ -100,13 +248,7 @@ impl PermMix for UVec2 {
 #[cfg(feature = "glam")]
 impl PermMix for IVec3 {
     fn perm_mix(self, perm: &[u8; 512]) -> u8 {
-        perm[
-            (self.x & 255) as usize + perm[
-                (self.y & 255) as usize + perm[
-                    (self.z & 255) as usize
-                ] as usize
-            ] as usize
-        ]
+        (self.x, self.y, self.z).perm_mix(perm)
     }
 }
 
@@ -153,6 +295,24 @@ impl PermMix for UVec4 {
     }
 }
 
+impl PermMix for (i32, i32) {
+    fn perm_mix(self, perm: &[u8; 512]) -> u8 {
+        perm[(self.0 & 255) as usize + perm[(self.1 & 255) as usize] as usize]
+    }
+}
+
+impl PermMix for (i32, i32, i32) {
+    fn perm_mix(self, perm: &[u8; 512]) -> u8 {
+        perm[
+            (self.0 & 255) as usize + perm[
+                (self.1 & 255) as usize + perm[
+                    (self.2 & 255) as usize
+                ] as usize
+            ] as usize
+        ]
+    }
+}
+
 impl PermMix for u64 {
     fn perm_mix(self, perm: &[u8; 512]) -> u8 {
         u32::perm_mix(self as u32, perm)
@@ -209,7 +369,7 @@ impl PermMix for i16 {
 
 impl PermMix for u8 {
     fn perm_mix(self, perm: &[u8; 512]) -> u8 {
-        perm[(self & 255) as usize]
+        perm[self as usize]
     }
 }
 
@@ -217,4 +377,99 @@ impl PermMix for i8 {
     fn perm_mix(self, perm: &[u8; 512]) -> u8 {
         perm[self as usize & 255]
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn tuples_mix_like_glam_vectors() {
+        use glam::{IVec2, IVec3};
+        let perm = Permutation::with_seed(0);
+        for x in -20..20 {
+            for y in -20..20 {
+                assert_eq!(perm.mix((x, y)), perm.mix(IVec2::new(x, y)));
+                assert_eq!(perm.mix((x, y, x ^ y)), perm.mix(IVec3::new(x, y, x ^ y)));
+            }
+        }
+    }
+
+    #[test]
+    fn derive_depends_on_the_salt() {
+        let base = Permutation::with_seed(0);
+        let a = base.derive(1);
+        assert_eq!(a.as_bytes(), base.derive(1).as_bytes());
+        assert_ne!(a.as_bytes(), base.derive(2).as_bytes());
+        assert_ne!(a.as_bytes(), base.as_bytes());
+        assert_eq!(a.source_seed(), None);
+        let mut sorted = a.as_bytes().to_vec();
+        sorted.sort_unstable();
+        assert!(sorted.iter().enumerate().all(|(i, &b)| b as usize == i));
+    }
+
+    #[test]
+    fn base64_round_trip_and_errors() {
+        let perm = Permutation::with_seed(0);
+        let encoded = perm.to_base64();
+        assert_eq!(encoded.len(), 344);
+        let decoded = Permutation::from_base64(&encoded).unwrap();
+        assert_eq!(decoded.as_bytes(), perm.as_bytes());
+
+        let err = |s: &str| Permutation::from_base64(s).map(|_| ()).unwrap_err();
+        assert_eq!(err("abc"), PermError::InvalidBase64);
+        assert_eq!(err(&format!("!{}", &encoded[1..])), PermError::InvalidBase64);
+        assert_eq!(err("=AAA"), PermError::InvalidBase64);
+        assert_eq!(err("AAAA"), PermError::InvalidLength(3));
+        assert_eq!(err(&("A".repeat(340) + "AA==")), PermError::NotAPermutation);
+
+        // the char before the padding carries 2 data bits and 4 padding bits,
+        // setting a padding bit must not give a second encoding of the table
+        let mut tweaked = encoded.into_bytes();
+        let last = BASE64.iter().position(|&b| b == tweaked[341]).unwrap();
+        tweaked[341] = BASE64[last ^ 1];
+        assert_eq!(err(std::str::from_utf8(&tweaked).unwrap()), PermError::InvalidBase64);
+    }
+
+    #[test]
+    fn mix_salted_depends_on_the_salt() {
+        let perm = Permutation::with_seed(0);
+        for v in 0..256u32 {
+            assert_eq!(perm.mix_salted(v, 0), perm.mix_salted(v, 0));
+        }
+        let differing = (0..256u32).filter(|&v| perm.mix_salted(v, 1) != perm.mix_salted(v, 2)).count();
+        assert!(differing > 200, "only {differing} values changed with the salt");
+        // a salted mix is still a permutation of the inputs
+        let mut outputs: Vec<u8> = (0..256u32).map(|v| perm.mix_salted(v, 7)).collect();
+        outputs.sort_unstable();
+        assert!(outputs.iter().enumerate().all(|(i, &b)| b as usize == i));
+    }
+
+    #[test]
+    fn index_wraps_past_255() {
+        let perm = Permutation::with_seed(0);
+        for i in 0..256 {
+            assert_eq!(perm[i], perm.as_bytes()[i]);
+            assert_eq!(perm[i + 256], perm[i]);
+            assert_eq!(perm[i + 1024 * 256], perm[i]);
+        }
+        assert_eq!(perm[usize::MAX], perm[255]);
+    }
+
+    #[test]
+    fn source_seed_is_kept_only_for_seeded_tables() {
+        assert_eq!(Permutation::with_seed(5).source_seed(), Some(5));
+        assert_eq!(Permutation::with_seed(5).derive(1).source_seed(), None);
+        assert_eq!(Permutation::from_bytes(*Permutation::DEFAULT.first_chunk().unwrap()).source_seed(), None);
+    }
+
+    #[test]
+    fn from_str_is_keyed_by_the_string() {
+        let world = Permutation::from_str("overworld");
+        assert_eq!(world.as_bytes(), Permutation::from_str("overworld").as_bytes());
+        assert_ne!(world.as_bytes(), Permutation::from_str("nether").as_bytes());
+        assert_ne!(world.as_bytes(), Permutation::from_str("overworld ").as_bytes());
+        assert_eq!(world.source_seed(), Some(crate::seed::from_str("overworld")));
+    }
+}
@@ -0,0 +1,100 @@
+//! Low-discrepancy (quasi-random) sequences.
+//!
+//! These don't use an RNG at all. They produce deterministic sequences
+//! that cover the unit interval more evenly than random numbers, which
+//! is often what you want for sampling and placement.
+
+/// The Halton sequence for a single prime base.
+///
+/// For base 2 this yields 1/2, 1/4, 3/4, 1/8, 5/8, ...
+/// Use coprime bases (e.g. 2 and 3) for each dimension of a multi-dimensional sequence.
+#[derive(Copy, Clone, Debug)]
+pub struct Halton {
+    base: u64,
+    index: u64,
+}
+
+impl Halton {
+    /// Construct a Halton sequence with the given base, starting at index 1.
+    ///
+    /// Panics if `base < 2`.
+    pub fn new(base: u64) -> Self {
+        assert!(base >= 2, "Halton base must be at least 2");
+        Self { base, index: 1 }
+    }
+
+    /// Get the next value in the sequence, in the range (0, 1).
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> f64 {
+        let value = radical_inverse(self.index, self.base);
+        self.index += 1;
+        value
+    }
+}
+
+/// The `index`th value of the van der Corput sequence in `base`, in [0, 1).
+///
+/// Mirrors the digits of `index` around the radix point, so for base 2
+/// indices 1, 2, 3, 4 give 1/2, 1/4, 3/4, 1/8. This is the Halton sequence
+/// indexed directly. Panics if `base < 2`.
+pub fn van_der_corput(index: u64, base: u64) -> f64 {
+    assert!(base >= 2, "van der Corput base must be at least 2");
+    radical_inverse(index, base)
+}
+
+/// The `index`th point of the first two dimensions of the Sobol sequence,
+/// in [0, 1)^2. Index 0 is the origin.
+///
+/// The first dimension is the base-2 van der Corput sequence (bit reversal),
+/// and the second uses the direction numbers of the polynomial `x + 1`.
+/// Every power-of-two prefix of the sequence is well stratified in both axes.
+pub fn sobol2(index: u32) -> [f64; 2] {
+    let (mut x, mut y) = (0u32, 0u32);
+    let mut direction = 1u32 << 31;
+    for bit in 0..32 {
+        if index >> bit & 1 == 1 {
+            x ^= 1 << (31 - bit);
+            y ^= direction;
+        }
+        direction ^= direction >> 1;
+    }
+    let scale = 1.0 / (1u64 << 32) as f64;
+    [x as f64 * scale, y as f64 * scale]
+}
+
+/// Mirror the digits of `index` in `base` around the radix point.
+fn radical_inverse(mut index: u64, base: u64) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut scale = inv_base;
+    let mut result = 0.0;
+    while index > 0 {
+        result += (index % base) as f64 * scale;
+        index /= base;
+        scale *= inv_base;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn halton_base_2_reference_values() {
+        let mut halton = Halton::new(2);
+        let expected = [0.5, 0.25, 0.75, 0.125, 0.625, 0.375, 0.875, 0.0625];
+        for e in expected {
+            assert_eq!(halton.next(), e);
+        }
+    }
+
+    #[test]
+    fn van_der_corput_base_2_reference_values() {
+        let expected = [0.0, 0.5, 0.25, 0.75, 0.125, 0.625, 0.375, 0.875];
+        for (i, e) in expected.into_iter().enumerate() {
+            assert_eq!(van_der_corput(i as u64, 2), e);
+        }
+        let mut halton = Halton::new(3);
+        assert!((1..50).all(|i| halton.next() == van_der_corput(i, 3)));
+    }
+}
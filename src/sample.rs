@@ -0,0 +1,284 @@
+//! Stateful samplers that draw from collections.
+
+use std::collections::VecDeque;
+use std::ops::Range;
+use crate::wyrand::{WyRand, RangeRng};
+
+/// Chooses elements of a slice at random, never returning an element
+/// that was one of the last `window` picks.
+///
+/// If `window` is at least the slice length, it is reduced to `len - 1`:
+/// every element is picked once before any repeats, and the element
+/// picked longest ago becomes available again next.
+pub struct NoRepeatChooser<'a, T> {
+    items: &'a [T],
+    window: usize,
+    /// Indices of the most recent picks, oldest first.
+    recent: VecDeque<usize>,
+}
+
+impl<'a, T> NoRepeatChooser<'a, T> {
+    /// Construct the chooser. Panics if `items` is empty.
+    pub fn new(items: &'a [T], window: usize) -> Self {
+        assert!(!items.is_empty(), "NoRepeatChooser requires at least one item");
+        let window = window.min(items.len() - 1);
+        Self {
+            items,
+            window,
+            recent: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Pick an element that isn't one of the last `window` picks.
+    pub fn pick(&mut self, rng: &mut WyRand) -> &'a T {
+        let available = self.items.len() - self.recent.len();
+        let index = (0..self.items.len())
+            .filter(|i| !self.recent.contains(i))
+            .nth(rng.next_in_range(0..available))
+            .expect("an index outside the window is always available");
+
+        if self.window > 0 {
+            if self.recent.len() == self.window {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(index);
+        }
+        &self.items[index]
+    }
+}
+
+/// A bag of items with integer counts, drawn without replacement,
+/// like drawing colored balls from an urn or cards from a deck.
+#[derive(Clone, Debug)]
+pub struct DrawBag<T> {
+    items: Vec<(T, u64)>,
+    remaining: u64,
+}
+
+impl<T: Clone> DrawBag<T> {
+    /// Construct the bag from items and how many of each it holds.
+    pub fn new(items: impl IntoIterator<Item = (T, u64)>) -> Self {
+        let items: Vec<(T, u64)> = items.into_iter().filter(|(_, n)| *n > 0).collect();
+        let remaining = items.iter().map(|(_, n)| n).sum();
+        Self { items, remaining }
+    }
+
+    /// Draw one item, with probability proportional to its remaining count,
+    /// and remove it from the bag. Returns `None` when the bag is empty.
+    pub fn draw(&mut self, rng: &mut WyRand) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let mut k = rng.next_in_range(0..self.remaining);
+        let i = self.items
+            .iter()
+            .position(|(_, n)| match k.checked_sub(*n) {
+                Some(rest) => { k = rest; false }
+                None => true,
+            })
+            .expect("remaining is the sum of the counts");
+
+        self.remaining -= 1;
+        self.items[i].1 -= 1;
+        if self.items[i].1 == 0 {
+            Some(self.items.swap_remove(i).0)
+        } else {
+            Some(self.items[i].0.clone())
+        }
+    }
+
+    /// The number of items left in the bag.
+    pub fn len(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Whether the bag is empty.
+    pub fn is_empty(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+/// Chooses timestamped items with probability decaying exponentially
+/// in age, e.g. to simulate cache workloads where recent entries are hot.
+///
+/// An item's weight halves every `half_life` units of age, so an item
+/// `half_life` older than another is chosen half as often.
+#[derive(Clone, Debug)]
+pub struct RecencySampler<T> {
+    items: Vec<(T, f64)>,
+    half_life: f64,
+}
+
+impl<T> RecencySampler<T> {
+    /// Construct the sampler from items and their timestamps.
+    ///
+    /// Panics if `half_life` is not positive.
+    pub fn new(items: impl IntoIterator<Item = (T, f64)>, half_life: f64) -> Self {
+        assert!(half_life > 0.0, "RecencySampler requires a positive half_life");
+        Self {
+            items: items.into_iter().collect(),
+            half_life,
+        }
+    }
+
+    /// Add an item with its timestamp.
+    pub fn push(&mut self, item: T, timestamp: f64) {
+        self.items.push((item, timestamp));
+    }
+
+    /// Choose an item, weighting each by `0.5^(age / half_life)` where
+    /// `age = now - timestamp`. Items timestamped after `now` count as age 0.
+    ///
+    /// Panics if the sampler is empty.
+    pub fn sample(&self, rng: &mut WyRand, now: f64) -> &T {
+        assert!(!self.items.is_empty(), "RecencySampler::sample requires at least one item");
+        let age = |t: f64| (now - t).max(0.0);
+        // measure ages from the youngest item, so old
+        // items can't underflow every weight to zero
+        let youngest = self.items.iter().map(|(_, t)| age(*t)).fold(f64::INFINITY, f64::min);
+        let weights: Vec<f64> = self.items
+            .iter()
+            .map(|(_, t)| (-(age(*t) - youngest) / self.half_life).exp2())
+            .collect();
+        let i = rng.weighted_index(&weights).expect("the youngest item has weight 1");
+        &self.items[i].0
+    }
+
+    /// The number of items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// A range captured for repeated rolls, created with `WyRand::die`.
+///
+/// `roll` draws with any rng, and matches `rng.next_in_range(range)`.
+/// The die also carries its own rng, seeded from the rng that created it,
+/// so it can be used directly as an endless `Iterator` of rolls.
+#[derive(Clone)]
+pub struct Die<T> {
+    range: Range<T>,
+    rng: WyRand,
+}
+
+impl<T: RangeRng + Copy> Die<T> {
+    /// Roll the die using the provided rng.
+    pub fn roll(&self, rng: &mut WyRand) -> T {
+        rng.next_in_range_ref(&self.range)
+    }
+
+    /// The range the die rolls in.
+    pub fn range(&self) -> Range<T> {
+        self.range.start..self.range.end
+    }
+}
+
+impl<T: RangeRng + Copy> Iterator for Die<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        Some(self.rng.next_in_range_ref(&self.range))
+    }
+}
+
+impl WyRand {
+    /// Capture a range as a `Die` for repeated rolls. The die's own rng,
+    /// used when iterating, is seeded from this rng.
+    pub fn die<T: RangeRng + Copy>(&mut self, range: Range<T>) -> Die<T> {
+        Die {
+            range,
+            rng: WyRand::with_seed(self.next()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_repeat_chooser_respects_the_window() {
+        let mut rng = WyRand::with_seed(0);
+        let items = [0, 1, 2, 3, 4, 5, 6, 7];
+        let mut chooser = NoRepeatChooser::new(&items, 3);
+        let picks: Vec<i32> = (0..10_000).map(|_| *chooser.pick(&mut rng)).collect();
+        for w in picks.windows(4) {
+            assert!(!w[..3].contains(&w[3]), "{w:?}");
+        }
+        assert!(items.iter().all(|i| picks.contains(i)));
+
+        // a window as large as the slice cycles through every item
+        let mut chooser = NoRepeatChooser::new(&items, 100);
+        for _ in 0..10 {
+            let mut round: Vec<i32> = (0..8).map(|_| *chooser.pick(&mut rng)).collect();
+            round.sort_unstable();
+            assert_eq!(round, items);
+        }
+    }
+
+    #[test]
+    fn draw_bag_empties_with_the_right_counts() {
+        let mut rng = WyRand::with_seed(0);
+        let mut bag = DrawBag::new([('r', 5), ('g', 3), ('b', 0), ('y', 2)]);
+        assert_eq!(bag.len(), 10);
+        let mut drawn = Vec::new();
+        while let Some(item) = bag.draw(&mut rng) {
+            drawn.push(item);
+        }
+        assert!(bag.is_empty());
+        assert_eq!(bag.draw(&mut rng), None);
+        drawn.sort_unstable();
+        assert_eq!(drawn.iter().collect::<String>(), "gggrrrrryy");
+
+        // the first draw is proportional to the counts
+        let mut first_red = 0;
+        for _ in 0..10_000 {
+            let mut bag = DrawBag::new([('r', 5), ('g', 3), ('y', 2)]);
+            first_red += (bag.draw(&mut rng) == Some('r')) as u32;
+        }
+        assert!((4_800..5_200).contains(&first_red), "{first_red}");
+    }
+
+    #[test]
+    fn die_rolls_match_next_in_range() {
+        let mut rng = WyRand::with_seed(0);
+        let mut same = WyRand::with_seed(0);
+        let die = rng.die(1..7u32);
+        same.next::<u64>();
+        assert_eq!(die.range(), 1..7);
+        for _ in 0..1_000 {
+            assert_eq!(die.roll(&mut rng), same.next_in_range(1..7u32));
+        }
+        let rolls: Vec<u32> = die.clone().take(1_000).collect();
+        assert!(rolls.iter().all(|r| (1..7).contains(r)));
+        assert_eq!(rolls, die.take(1_000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn recency_sampler_halves_per_half_life() {
+        let mut rng = WyRand::with_seed(0);
+        let share_new = |sampler: &RecencySampler<&str>, rng: &mut WyRand, now: f64| {
+            (0..30_000).filter(|_| *sampler.sample(rng, now) == "new").count() as f64 / 30_000.0
+        };
+
+        // "old" is one half-life older, so it has half the weight
+        let mut sampler = RecencySampler::new([("old", 0.0)], 10.0);
+        sampler.push("new", 10.0);
+        assert_eq!(sampler.len(), 2);
+        let share = share_new(&sampler, &mut rng, 10.0);
+        assert!((share - 2.0 / 3.0).abs() < 0.015, "{share}");
+        // only relative age matters, even for very old items
+        let share = share_new(&sampler, &mut rng, 1e6);
+        assert!((share - 2.0 / 3.0).abs() < 0.015, "{share}");
+
+        // a shorter half-life decays faster: two half-lives is a quarter
+        let sampler = RecencySampler::new([("old", 0.0), ("new", 10.0)], 5.0);
+        let share = share_new(&sampler, &mut rng, 10.0);
+        assert!((share - 0.8).abs() < 0.015, "{share}");
+    }
+}
@@ -2,7 +2,7 @@ use std::cell::RefCell;
 use crate::wyrand::WyRand;
 
 thread_local! {
-    static THREAD_RNG: RefCell<Option<WyRand>> = RefCell::new(None);
+    static THREAD_RNG: RefCell<Option<WyRand>> = const { RefCell::new(None) };
 }
 
 /// This uses seed_from_system to generate a thread-local hash state. 
@@ -13,8 +13,53 @@ pub fn from_local() -> u64 {
     })
 }
 
-/// Generate an rng seed with getrandom on x86 and 
-/// web_time::SystemTime on wasm. 
+/// Whether the thread-local rng has been seeded on this thread. If not, the
+/// next call to `from_local` will seed it from system entropy.
+pub fn is_local_initialized() -> bool {
+    THREAD_RNG.with_borrow(|state| state.is_some())
+}
+
+/// Seed the thread-local rng from system entropy now, if it isn't already,
+/// so the first `from_local` call doesn't pay for it at an inopportune time.
+pub fn init_local() {
+    THREAD_RNG.with_borrow_mut(|state| {
+        state.get_or_insert_with(|| WyRand::with_seed(from_system()));
+    });
+}
+
+/// Replace the thread-local rng with one seeded deterministically.
+/// Useful for making code that calls `justrng::next()` reproducible in tests.
+pub fn reseed_local(seed: u64) {
+    THREAD_RNG.with_borrow_mut(|state| *state = Some(WyRand::with_seed(seed)));
+}
+
+/// Clear the thread-local rng, so the next call to `from_local`
+/// re-initializes it from system entropy.
+pub fn clear_local() {
+    THREAD_RNG.with_borrow_mut(|state| *state = None);
+}
+
+/// Expand a master seed into `count` decorrelated sub-seeds, so a single
+/// world seed can deterministically feed many subsystems. Sub-seed `i`
+/// is a hash of the master seed and `i`, so it doesn't depend on `count`.
+pub fn expand(master: u64, count: usize) -> Vec<u64> {
+    (0..count as u64)
+        .map(|i| crate::wyrand::mix2(master, i))
+        .collect()
+}
+
+/// Hash a string into a seed, so worlds can be keyed by a name
+/// instead of a number. The same string always gives the same seed.
+pub fn from_str(s: &str) -> u64 {
+    s.as_bytes().chunks(8).fold(s.len() as u64, |hash, chunk| {
+        let mut word = [0u8; 8];
+        word[..chunk.len()].copy_from_slice(chunk);
+        crate::wyrand::mix2(hash, u64::from_le_bytes(word))
+    })
+}
+
+/// Generate an rng seed with getrandom on x86 and
+/// web_time::SystemTime on wasm.
 #[cfg(not(target_arch = "wasm32"))]
 pub fn from_system() -> u64 {
     match getrandom::u64() {
@@ -41,5 +86,110 @@ pub fn from_system() -> u64 {
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_nanos();
-    ((nanos >> 64) ^ nanos) as u64
+    from_time(nanos)
+}
+
+/// Hash a clock reading into a seed. The clock can return the same time
+/// for calls in a tight loop, so this mixes in a per-thread call counter
+/// to keep successive seeds distinct.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+fn from_time(nanos: u128) -> u64 {
+    let count = SYSTEM_SEED_COUNT.with(|count| {
+        let n = count.get();
+        count.set(n.wrapping_add(1));
+        n
+    });
+    crate::wyrand::mix2(((nanos >> 64) ^ nanos) as u64, count)
+}
+
+thread_local! {
+    static SYSTEM_SEED_COUNT: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+}
+
+/// A source of rng seeds, for injecting seeds into constructors
+/// like `WyRand::new_with_source`.
+pub trait SeedSource {
+    fn seed(&self) -> u64;
+}
+
+/// Seeds from the thread-local rng state, like `from_local`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LocalSource;
+
+impl SeedSource for LocalSource {
+    fn seed(&self) -> u64 {
+        from_local()
+    }
+}
+
+/// Seeds from system entropy, like `from_system`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemSource;
+
+impl SeedSource for SystemSource {
+    fn seed(&self) -> u64 {
+        from_system()
+    }
+}
+
+/// Always produces the same seed, for tests and deterministic builds.
+#[derive(Copy, Clone, Debug)]
+pub struct FixedSource(pub u64);
+
+impl SeedSource for FixedSource {
+    fn seed(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reseed_local_is_deterministic() {
+        reseed_local(42);
+        let a: Vec<u64> = (0..8).map(|_| from_local()).collect();
+        reseed_local(42);
+        let b: Vec<u64> = (0..8).map(|_| from_local()).collect();
+        assert_eq!(a, b);
+        assert_eq!(a[0], WyRand::with_seed(42).next::<u64>());
+    }
+
+    #[test]
+    fn expand_is_distinct_and_reproducible() {
+        let seeds = expand(42, 1_000);
+        let mut unique = seeds.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), 1_000);
+        assert_eq!(seeds, expand(42, 1_000));
+        assert_eq!(&seeds[..10], &expand(42, 10)[..]);
+        assert_ne!(seeds[..10], expand(43, 10)[..]);
+    }
+
+    #[test]
+    fn local_rng_starts_uninitialized_on_new_threads() {
+        std::thread::spawn(|| {
+            assert!(!is_local_initialized());
+            init_local();
+            assert!(is_local_initialized());
+            clear_local();
+            assert!(!is_local_initialized());
+            from_local();
+            assert!(is_local_initialized());
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn equal_clock_readings_give_distinct_seeds() {
+        let nanos = 1_700_000_000_000_000_000;
+        let seeds: Vec<u64> = (0..100).map(|_| from_time(nanos)).collect();
+        let mut unique = seeds.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), seeds.len());
+    }
 }
@@ -0,0 +1,86 @@
+use std::sync::{Mutex, MutexGuard};
+use crate::wyrand::{WyRand, FromRng, SampleRange};
+
+/// A WyRand that can be shared between threads, guarded by a Mutex.
+///
+/// Every call takes the lock, so threads drawing heavily from the same
+/// SharedRng will contend with each other. For hot loops, prefer a WyRand
+/// per thread (e.g. `WyRand::new()`, seeded from the thread-local state).
+/// SharedRng is for occasional draws where passing an rng around is awkward.
+pub struct SharedRng(Mutex<WyRand>);
+
+impl SharedRng {
+    /// Construct a SharedRng with a seed from the thread-local rng state.
+    pub fn new() -> Self {
+        Self::from_rng(WyRand::new())
+    }
+
+    /// Construct a SharedRng with your own seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_rng(WyRand::with_seed(seed))
+    }
+
+    /// Wrap an existing WyRand.
+    pub fn from_rng(rng: WyRand) -> Self {
+        Self(Mutex::new(rng))
+    }
+
+    /// Generate a value.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next<T: FromRng>(&self) -> T {
+        self.lock().next()
+    }
+
+    /// Generate a value within a range.
+    pub fn next_in_range<T>(&self, range: impl SampleRange<T>) -> T {
+        self.lock().next_in_range(range)
+    }
+
+    /// Shuffle a slice, with every permutation equally likely.
+    pub fn shuffle<T>(&self, slice: &mut [T]) {
+        self.lock().shuffle_range(slice, 0..slice.len())
+    }
+
+    /// Lock the inner rng, for several draws under one lock.
+    pub fn lock(&self) -> MutexGuard<'_, WyRand> {
+        // a panic while holding the lock can't leave a WyRand in a bad state.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for SharedRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threads_share_one_sequence() {
+        let shared = SharedRng::with_seed(0);
+        let mut draws: Vec<u64> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| (0..1_000).map(|_| shared.next::<u64>()).collect::<Vec<u64>>()))
+                .collect();
+            handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+        });
+        // every thread drew from the same stream, with no draw lost or repeated
+        let mut expected = crate::wyrand::golden_sequence(0, 4_000);
+        draws.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(draws, expected);
+    }
+
+    #[test]
+    fn shuffle_keeps_elements() {
+        let shared = SharedRng::with_seed(0);
+        let mut items: Vec<u32> = (0..50).collect();
+        shared.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,108 @@
+//! Generators for test fixture data, enabled with the `testdata` feature.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::wyrand::WyRand;
+
+impl WyRand {
+    /// Generate a random IPv4 address.
+    pub fn next_ipv4(&mut self) -> Ipv4Addr {
+        Ipv4Addr::from_bits(self.next::<u32>())
+    }
+
+    /// Generate a random IPv6 address.
+    pub fn next_ipv6(&mut self) -> Ipv6Addr {
+        let hi = self.next::<u64>() as u128;
+        let lo = self.next::<u64>() as u128;
+        Ipv6Addr::from_bits(hi << 64 | lo)
+    }
+
+    /// Generate a pronounceable nonsense word of `min_len..=max_len` lowercase
+    /// letters, alternating between consonants and vowels.
+    pub fn next_word(&mut self, min_len: usize, max_len: usize) -> String {
+        assert!(min_len <= max_len, "next_word requires min_len <= max_len");
+        let len = self.next_in_range(min_len..max_len + 1);
+        let mut vowel = self.coin();
+        (0..len)
+            .map(|_| {
+                let set = if vowel { VOWELS } else { CONSONANTS };
+                vowel = !vowel;
+                set[self.next_in_range(0..set.len())] as char
+            })
+            .collect()
+    }
+
+    /// Generate a sentence of `min_words..=max_words` nonsense words, capitalized
+    /// and ending with a period.
+    pub fn next_sentence(&mut self, min_words: usize, max_words: usize) -> String {
+        assert!(min_words <= max_words, "next_sentence requires min_words <= max_words");
+        let count = self.next_in_range(min_words..max_words + 1);
+        let mut sentence = (0..count)
+            .map(|_| self.next_word(2, 8))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if let Some(first) = sentence.get_mut(..1) {
+            first.make_ascii_uppercase();
+        }
+        sentence.push('.');
+        sentence
+    }
+
+    /// Generate a random 6 byte MAC address.
+    pub fn next_mac(&mut self) -> [u8; 6] {
+        let bytes = self.next::<u64>().to_le_bytes();
+        [bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5]]
+    }
+}
+
+const VOWELS: &[u8] = b"aeiou";
+const CONSONANTS: &[u8] = b"bcdfghjklmnprstvwz";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addresses_are_deterministic_and_round_trip() {
+        let mut a = WyRand::with_seed(0);
+        let mut b = WyRand::with_seed(0);
+        for _ in 0..1_000 {
+            let v4 = a.next_ipv4();
+            assert_eq!(v4, b.next_ipv4());
+            assert_eq!(v4.to_string().parse::<Ipv4Addr>().unwrap(), v4);
+            let v6 = a.next_ipv6();
+            assert_eq!(v6, b.next_ipv6());
+            assert_eq!(v6.to_string().parse::<Ipv6Addr>().unwrap(), v6);
+            assert_eq!(a.next_mac(), b.next_mac());
+        }
+    }
+
+    #[test]
+    fn next_word_length_and_determinism() {
+        let mut a = WyRand::with_seed(0);
+        let mut b = WyRand::with_seed(0);
+        for _ in 0..1_000 {
+            let word = a.next_word(3, 7);
+            assert!((3..=7).contains(&word.len()));
+            assert!(word.bytes().all(|c| c.is_ascii_lowercase()));
+            assert_eq!(word, b.next_word(3, 7));
+        }
+        assert_eq!(a.next_word(4, 4).len(), 4);
+    }
+
+    #[test]
+    fn next_sentence_shape() {
+        let mut rng = WyRand::with_seed(0);
+        let mut counts = [0u32; 6];
+        for _ in 0..1_000 {
+            let sentence = rng.next_sentence(2, 5);
+            let body = sentence.strip_suffix('.').expect("ends with a period");
+            assert!(body.starts_with(|c: char| c.is_ascii_uppercase()), "{sentence}");
+            let words: Vec<&str> = body.split(' ').collect();
+            assert!((2..=5).contains(&words.len()), "{sentence}");
+            assert!(words.iter().all(|w| (2..=8).contains(&w.len())));
+            counts[words.len()] += 1;
+        }
+        assert!(counts[2..].iter().all(|&c| c > 0), "{counts:?}");
+        assert_eq!(rng.next_sentence(0, 0), ".");
+    }
+}
@@ -1,17 +1,28 @@
 
-use std::ops::Range;
+use std::ops::{Range, RangeFrom, RangeTo};
 #[cfg(feature = "glam")]
-use glam::{IVec2, IVec3, IVec4, UVec2, UVec3, UVec4};
+use glam::{IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, Vec2, Vec3, Mat2, Mat3, Quat, Affine2};
 use crate::primes::*;
+use crate::error::RngError;
+use crate::seed::SeedSource;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
 
 /// A small, highly efficient WyRand implementation.
 #[derive(Copy, Clone)]
 pub struct WyRand {
     /// The current value of the RNG.
     state: u64,
+    /// Max attempts for rejection sampling loops before giving up.
+    rejection_cap: u32,
+    /// How many times the state has advanced since seeding.
+    draws: u64,
 }
 
 impl WyRand {
+    /// The rejection cap WyRand instances are constructed with.
+    pub const DEFAULT_REJECTION_CAP: u32 = 1 << 20;
+
     /// Construct a new WyRand instance.
     pub fn new() -> Self {
         Self::with_local_seed()
@@ -19,7 +30,25 @@ impl WyRand {
 
     /// Construct a new WyRand instance with your own seed.
     pub fn with_seed(seed: u64) -> Self {
-        Self { state: seed }
+        Self {
+            state: seed,
+            rejection_cap: Self::DEFAULT_REJECTION_CAP,
+            draws: 0,
+        }
+    }
+
+    /// Replace the state with a new seed, keeping the rejection cap,
+    /// and reset `draws_since_seed` to 0.
+    pub fn reseed(&mut self, seed: u64) {
+        self.state = seed;
+        self.draws = 0;
+    }
+
+    /// How many values have been generated since this rng was seeded or
+    /// last reseeded, for long-running simulations that reseed periodically.
+    /// Every draw goes through `next`, so this counts each advance of the state.
+    pub fn draws_since_seed(&self) -> u64 {
+        self.draws
     }
 
     /// Construct the WyRand instance with a seed generated from the
@@ -37,24 +66,1144 @@ impl WyRand {
         Self::with_seed(crate::seed::from_system())
     }
 
+    /// Construct a WyRand instance with a seed from the provided source.
+    pub fn new_with_source<S: SeedSource>(source: &S) -> Self {
+        Self::with_seed(source.seed())
+    }
+
+    /// Construct a WyRand instance from an 8 byte seed, for APIs
+    /// that pass seeds as `[u8; 8]` (like rand's `SeedableRng`).
+    /// The rejection cap is the default, see `to_seed`.
+    pub fn from_rng_seed(seed: [u8; 8]) -> Self {
+        Self::with_seed(u64::from_le_bytes(seed))
+    }
+
+    /// Construct a child rng for an independent subsystem, advancing
+    /// this rng exactly once. The child keeps this rng's rejection cap.
+    ///
+    /// The child is seeded from a hashed output rather than the raw state,
+    /// so its stream starts at an unrelated point in the sequence instead
+    /// of repeating this rng's continuation.
+    pub fn spawn_child(&mut self) -> WyRand {
+        Self {
+            rejection_cap: self.rejection_cap,
+            ..Self::with_seed(self.next())
+        }
+    }
+
+    /// Get the current state as an 8 byte seed. Passing it to
+    /// `from_rng_seed` produces an rng that continues from the current position.
+    ///
+    /// Only the state is captured. A custom rejection cap is not part of the
+    /// seed, so set it again on the restored rng if it matters.
+    pub fn to_seed(&self) -> [u8; 8] {
+        self.state.to_le_bytes()
+    }
+
+    /// Set the maximum number of attempts rejection-based methods make
+    /// before returning `RngError::TooManyRejections`, so a pathological
+    /// case can't loop forever.
+    ///
+    /// The cap covers `try_next_where`, `derangement`, `next_truncated_normal`
+    /// and `Zipf`. Range and char methods like `next_in_range` and
+    /// `random_utf8` draw a fixed number of values and never loop, so the
+    /// cap doesn't apply to them.
+    pub fn set_rejection_cap(&mut self, cap: u32) {
+        self.rejection_cap = cap;
+    }
+
+    /// Get the maximum number of attempts rejection-based methods make.
+    pub fn rejection_cap(&self) -> u32 {
+        self.rejection_cap
+    }
+
+    /// Run a rejection sampling loop until `attempt` accepts a value,
+    /// or the rejection cap is reached.
+    pub(crate) fn retry<T>(&mut self, mut attempt: impl FnMut(&mut Self) -> Option<T>) -> Result<T, RngError> {
+        for _ in 0..self.rejection_cap {
+            if let Some(value) = attempt(self) {
+                return Ok(value);
+            }
+        }
+        Err(RngError::TooManyRejections)
+    }
+
     /// Generate a value by updating and hashing the state.
+    #[allow(clippy::should_implement_trait)]
     pub fn next<T: FromRng>(&mut self) -> T {
         self.state = self.state.wrapping_add(P0);
-        let r = u128::from(self.state).wrapping_mul(u128::from(self.state ^ P1));
-        T::from_rng((r.wrapping_shr(64) ^ r) as u64)
+        self.draws = self.draws.wrapping_add(1);
+        T::from_rng(mum(self.state, self.state ^ P1))
+    }
+
+    /// The value the next call to `next` will return, without advancing the state.
+    pub fn peek<T: FromRng>(&self) -> T {
+        let mut copy = *self;
+        copy.next()
+    }
+
+    /// Generate values until one satisfies the predicate.
+    /// 
+    /// Panics if the rejection cap is reached, e.g. for a predicate
+    /// that is never true. Use `try_next_where` to handle that case.
+    pub fn next_where<T: FromRng>(&mut self, pred: impl Fn(&T) -> bool) -> T {
+        self.try_next_where(pred)
+            .expect("next_where exceeded the rejection cap")
+    }
+
+    /// Generate values until one satisfies the predicate, returning
+    /// `RngError::TooManyRejections` if the rejection cap is reached.
+    pub fn try_next_where<T: FromRng>(&mut self, pred: impl Fn(&T) -> bool) -> Result<T, RngError> {
+        self.retry(|rng| Some(rng.next::<T>()).filter(|v| pred(v)))
     }
 
     /// Generate a value by updating and hashing the state, then wrapping to the range. 
-    pub fn next_in_range<T: RangeRng>(&mut self, range: Range<T>) -> T {
-        T::from_range(self.next(), range)
+    /// 
+    /// Accepts `start..end`, and for integers also `start..` (meaning `start..=T::MAX`)
+    /// and `..end` (meaning `T::MIN..end`).
+    ///
+    /// Floats are always in `[start, end)`. An empty or reversed float range
+    /// returns `start`.
+    pub fn next_in_range<T>(&mut self, range: impl SampleRange<T>) -> T {
+        range.sample(self.next())
+    }
+
+    /// Like `next_in_range`, but borrows the range, so one range can be reused
+    /// across many draws without cloning it.
+    pub fn next_in_range_ref<T: RangeRng + Copy>(&mut self, range: &Range<T>) -> T {
+        T::from_range(self.next(), range.start..range.end)
+    }
+
+    /// Generate an f32 in the range using only f32 math, for speed and
+    /// identical results across targets. Uses 24 random bits, which fill
+    /// the f32 mantissa.
+    pub fn next_f32_in_range(&mut self, range: Range<f32>) -> f32 {
+        f32::lerp_half_open(unit_f32(self.next()), range)
+    }
+
+    /// Generate a value in the range, also returning the `t` in [0, 1)
+    /// that produced it, so `t` can drive correlated secondary samples.
+    pub fn next_in_range_t(&mut self, range: Range<f64>) -> (f64, f64) {
+        let t = self.next_f64_full();
+        (range.start * (1.0 - t) + range.end * t, t)
+    }
+
+    /// Generate a value in the range, with `t` in [0, 1) passed through `shape`
+    /// before mapping into the range. `shape` must map [0, 1) into [0, 1);
+    /// e.g. `|t| t * t` biases results toward `range.start`.
+    pub fn next_in_range_shaped(&mut self, range: Range<f64>, shape: impl Fn(f64) -> f64) -> f64 {
+        let t = shape(self.next_f64_full());
+        range.start * (1.0 - t) + range.end * t
+    }
+
+    /// Generate a value in the range with the arcsine (Beta(1/2, 1/2))
+    /// distribution, which is U-shaped: values cluster near both endpoints,
+    /// for picking "extreme" procedural values.
+    pub fn next_u_shaped(&mut self, range: Range<f64>) -> f64 {
+        // inverse of the arcsine CDF, 2/pi * asin(sqrt(t))
+        let t = (std::f64::consts::FRAC_PI_2 * self.next_f64_full()).sin().powi(2);
+        range.start * (1.0 - t) + range.end * t
+    }
+
+    /// Generate a value in the range, excluding `exclude`.
+    /// 
+    /// Draws from a range one smaller and steps over `exclude`, so no retry
+    /// loop is needed. If `exclude` is outside the range, this is the same
+    /// as `next_in_range`.
+    /// 
+    /// Panics if `exclude` is the only value in the range.
+    pub fn next_in_range_excluding<T: StepRng>(&mut self, range: Range<T>, exclude: T) -> T {
+        if exclude < range.start || exclude >= range.end {
+            return self.next_in_range(range);
+        }
+        assert!(
+            range.start.forward() != range.end,
+            "next_in_range_excluding: the range contains only the excluded value"
+        );
+        let v = self.next_in_range(range.start..range.end.backward());
+        if v >= exclude { v.forward() } else { v }
+    }
+
+    /// Generate a value in `[center - radius, center + radius]`, for jittering
+    /// around a value that may be near the type's bounds.
+    ///
+    /// With `BandMode::Saturate` the band is clipped to the type's range and
+    /// the value is uniform over what remains. With `BandMode::Wrap` the band
+    /// wraps around the type's range, like wrapping arithmetic.
+    ///
+    /// Panics if `radius` is negative.
+    pub fn next_in_wrapping_range<T: WrapRng>(&mut self, center: T, radius: T, mode: BandMode) -> T {
+        let (c, r) = (center.to_i128(), radius.to_i128());
+        assert!(r >= 0, "next_in_wrapping_range: radius must be non-negative");
+        let (lo, hi) = match mode {
+            BandMode::Saturate => ((c - r).max(T::MIN), (c + r).min(T::MAX)),
+            BandMode::Wrap => (c - r, c + r),
+        };
+        let span = hi - lo + 1;
+        let offset = if span > T::MAX - T::MIN {
+            // the band covers every value, so any bits will do
+            self.next::<u64>() as i128
+        } else {
+            self.next_in_range(0..span as u64) as i128
+        };
+        T::from_i128_wrapping(lo + offset)
+    }
+
+    /// Flip an exactly fair coin, using the top bit of one generated value.
+    pub fn coin(&mut self) -> bool {
+        self.next::<bool>()
+    }
+
+    /// Generate a value in `[0, 2^bits)` from the top `bits` bits of one
+    /// generated value, for `bits <= 64`. Unlike a modulo, this is never biased.
+    pub fn next_bits(&mut self, bits: u32) -> u64 {
+        assert!(bits <= 64, "next_bits supports at most 64 bits");
+        match bits {
+            0 => 0,
+            _ => self.next::<u64>() >> (64 - bits),
+        }
+    }
+
+    /// Count the heads in `n` fair coin flips, for `n <= 64`.
+    /// Uses the popcount of a single generated value.
+    pub fn flip_n(&mut self, n: u32) -> u32 {
+        assert!(n <= 64, "flip_n supports at most 64 flips");
+        self.next_bits(n).count_ones()
+    }
+
+    /// Generate an f64 in [0, 1) with exactly uniform spacing.
+    /// 
+    /// Fills the 52 mantissa bits of a float in [1, 2), then subtracts 1,
+    /// so every output is a multiple of 2^-52. `next::<f64>()` divides by
+    /// `u64::MAX` instead, which can return 1.0 and rounds to uneven spacing;
+    /// it is kept as-is so existing seeds produce the same values.
+    pub fn next_f64_full(&mut self) -> f64 {
+        f64::from_bits(0x3FF0_0000_0000_0000 | (self.next::<u64>() >> 12)) - 1.0
+    }
+
+    /// Generate an f64 in the open interval (0, 1), never exactly 0 or 1.
+    /// Useful for log transforms. Uses the top 52 bits, offset by half a step.
+    pub fn next_open_unit(&mut self) -> f64 {
+        open_unit(self.next())
+    }
+
+    /// Generate `k` non-negative weights summing to 1, uniformly distributed
+    /// over the simplex. Useful for random convex combinations.
+    pub fn next_simplex_weights(&mut self, k: usize) -> Vec<f64> {
+        // normalized exponential samples are uniform over the simplex.
+        let mut weights: Vec<f64> = (0..k)
+            .map(|_| -(1.0 - self.next_f64_full()).ln())
+            .collect();
+        let total: f64 = weights.iter().sum();
+        if total == 0.0 {
+            return vec![1.0 / k as f64; k];
+        }
+        weights.iter_mut().for_each(|w| *w /= total);
+        weights
+    }
+
+    /// The Metropolis acceptance criterion, the core of simulated annealing.
+    /// Improving moves (`delta <= 0`) are always accepted, worsening moves
+    /// with probability `exp(-delta / temperature)`. At a temperature of 0
+    /// or below, worsening moves are never accepted.
+    pub fn accept(&mut self, delta: f64, temperature: f64) -> bool {
+        if delta <= 0.0 {
+            return true;
+        }
+        if temperature <= 0.0 {
+            return false;
+        }
+        (-delta / temperature).exp() > self.next_f64_full()
+    }
+
+    /// Sample a normal (gaussian) distribution using the Box-Muller transform.
+    pub fn next_normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        // keep u1 away from 0, ln(0) is -inf.
+        let u1 = (1.0 - self.next::<f64>()).max(f64::MIN_POSITIVE);
+        let u2 = self.next::<f64>();
+        let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        mean + std_dev * z
+    }
+
+    /// Sample a normal distribution and round to the nearest integer.
+    pub fn next_gaussian_int(&mut self, mean: f64, std_dev: f64) -> i64 {
+        self.next_normal(mean, std_dev).round() as i64
+    }
+
+    /// Sample a Pareto distribution with the given scale (minimum value) and
+    /// shape, for heavy-tailed quantities. Smaller shapes give heavier tails.
+    pub fn next_pareto(&mut self, scale: f64, shape: f64) -> f64 {
+        scale / self.next_open_unit().powf(1.0 / shape)
+    }
+
+    /// Sample a normal distribution truncated to `[low, high]`.
+    /// 
+    /// Uses Robert's rejection samplers on the standardized bounds: a normal
+    /// or uniform proposal when the bounds straddle the mean, and a uniform
+    /// or exponential proposal when they lie in one tail. Each accepts with
+    /// a probability bounded away from zero, so bounds far out in the tails
+    /// are as cheap as bounds near the mean. If the rejection cap is reached
+    /// anyway (only likely with a tiny cap), a fresh draw is clamped to the
+    /// bounds instead.
+    pub fn next_truncated_normal(&mut self, mean: f64, std_dev: f64, low: f64, high: f64) -> f64 {
+        assert!(low <= high, "next_truncated_normal requires low <= high");
+        if std_dev <= 0.0 || low == high {
+            return mean.clamp(low, high);
+        }
+        let (a, b) = ((low - mean) / std_dev, (high - mean) / std_dev);
+        // sample a standard normal on [a, b], mirroring the lower tail onto the upper
+        let (a, b, sign) = if b <= 0.0 { (-b, -a, -1.0) } else { (a, b, 1.0) };
+        let result = self.retry(|rng| {
+            if a <= 0.0 {
+                if b - a >= std::f64::consts::TAU.sqrt() {
+                    let z = rng.next_normal(0.0, 1.0);
+                    (a..=b).contains(&z).then_some(z)
+                } else {
+                    let z = rng.next_in_range(a..b);
+                    (rng.next::<f64>() < (-0.5 * z * z).exp()).then_some(z)
+                }
+            } else {
+                let root = (a * a + 4.0).sqrt();
+                let exponential_cutoff = a + 2.0 * 0.5f64.exp() / (a + root) * ((a * a - a * root) / 4.0).exp();
+                if b > exponential_cutoff {
+                    let alpha = (a + root) / 2.0;
+                    let z = a - rng.next_open_unit().ln() / alpha;
+                    (z <= b && rng.next::<f64>() < (-0.5 * (z - alpha) * (z - alpha)).exp()).then_some(z)
+                } else {
+                    let z = rng.next_in_range(a..b);
+                    (rng.next::<f64>() < (0.5 * (a * a - z * z)).exp()).then_some(z)
+                }
+            }
+        });
+        match result {
+            Ok(z) => (mean + std_dev * sign * z).clamp(low, high),
+            Err(_) => self.next_normal(mean, std_dev).clamp(low, high),
+        }
+    }
+
+    /// Generate an index in `[0, len)` that isn't in `excluded`, uniformly.
+    /// Draws from the reduced count and steps over the excluded indices, so no
+    /// retry loop is needed. Returns `None` if every index is excluded.
+    pub fn index_excluding(&mut self, len: usize, excluded: &[usize]) -> Option<usize> {
+        let mut excluded: Vec<usize> = excluded.iter().copied().filter(|&i| i < len).collect();
+        excluded.sort_unstable();
+        excluded.dedup();
+        let available = len - excluded.len();
+        if available == 0 {
+            return None;
+        }
+        let mut index = self.next_in_range(0..available);
+        for &e in &excluded {
+            if e <= index {
+                index += 1;
+            } else {
+                break;
+            }
+        }
+        Some(index)
+    }
+
+    /// A reproducible random sort key for the item with the given id.
+    /// 
+    /// Doesn't advance the rng, so the key depends only on the current state and
+    /// the id. Sorting items by the keys of stable ids gives a shuffle that is
+    /// the same on every run with the same seed, regardless of input order.
+    pub fn sort_key(&self, id: u64) -> u64 {
+        mix2(self.state, id)
     }
 
     /// Shuffle a slice 
+    ///
+    /// This swaps each position with any index, which makes some orderings
+    /// more likely than others. It is kept as-is so existing seeds produce
+    /// the same results; use `shuffle_with` for an unbiased shuffle.
     pub fn shuffle<T>(&mut self, slice: &mut [T]) {
         for i in 0..slice.len() {
             slice.swap(i, self.next_in_range(0..slice.len()))
         }
     }
+
+    /// Shuffle a slice with the chosen algorithm.
+    pub fn shuffle_with<T>(&mut self, slice: &mut [T], algo: ShuffleAlgo) {
+        match algo {
+            ShuffleAlgo::FisherYates => self.shuffle_range(slice, 0..slice.len()),
+            ShuffleAlgo::Sattolo => {
+                for i in (1..slice.len()).rev() {
+                    slice.swap(i, self.next_in_range(0..i));
+                }
+            }
+        }
+    }
+
+    /// Shuffle only `slice[range]` with Fisher-Yates, leaving the rest in place.
+    /// 
+    /// Panics if the range is out of bounds.
+    pub fn shuffle_range<T>(&mut self, slice: &mut [T], range: Range<usize>) {
+        let sub = &mut slice[range];
+        for i in (1..sub.len()).rev() {
+            sub.swap(i, self.next_in_range(0..i + 1));
+        }
+    }
+
+    /// Shuffle a slice while keeping items with equal keys adjacent.
+    /// 
+    /// Items are grouped by key, the order of the groups is shuffled, and the
+    /// items within each group are shuffled. The result is random, except that
+    /// every group of equal keys forms one contiguous run.
+    pub fn shuffle_by_key<T, K: Ord>(&mut self, slice: &mut [T], key: impl Fn(&T) -> K) {
+        slice.sort_by_key(&key);
+
+        // find the runs of equal keys
+        let mut groups = Vec::new();
+        let mut start = 0;
+        for i in 1..=slice.len() {
+            if i == slice.len() || key(&slice[i]) != key(&slice[start]) {
+                groups.push(start..i);
+                start = i;
+            }
+        }
+
+        for group in &groups {
+            self.shuffle_range(slice, group.clone());
+        }
+        let len = groups.len();
+        self.shuffle_range(&mut groups, 0..len);
+
+        // move the groups into their shuffled order, following the
+        // cycles of the permutation so T doesn't need to be Clone.
+        let order: Vec<usize> = groups.into_iter().flatten().collect();
+        let mut done = vec![false; order.len()];
+        for start in 0..order.len() {
+            let mut i = start;
+            while !done[i] {
+                done[i] = true;
+                let j = order[i];
+                if j == start {
+                    break;
+                }
+                slice.swap(i, j);
+                i = j;
+            }
+        }
+    }
+
+    /// Randomly assign each item to one of `groups` groups, independently and
+    /// uniformly, so group sizes can differ. See `split_balanced` for even sizes.
+    /// 
+    /// Panics if `groups` is 0.
+    pub fn partition<T: Clone>(&mut self, items: &[T], groups: usize) -> Vec<Vec<T>> {
+        assert!(groups > 0, "partition requires at least one group");
+        let mut result = vec![Vec::new(); groups];
+        for item in items {
+            result[self.next_in_range(0..groups)].push(item.clone());
+        }
+        result
+    }
+
+    /// Shuffle the items, then deal them round-robin into `groups` groups,
+    /// so group sizes differ by at most one. Useful for matchmaking.
+    /// The Vec is left empty.
+    /// 
+    /// Panics if `groups` is 0.
+    pub fn split_balanced<T>(&mut self, items: &mut Vec<T>, groups: usize) -> Vec<Vec<T>> {
+        assert!(groups > 0, "split_balanced requires at least one group");
+        let len = items.len();
+        self.shuffle_range(items, 0..len);
+        let mut result: Vec<Vec<T>> = (0..groups)
+            .map(|_| Vec::with_capacity(items.len().div_ceil(groups)))
+            .collect();
+        for (i, item) in items.drain(..).enumerate() {
+            result[i % groups].push(item);
+        }
+        result
+    }
+
+    /// Generate a uniformly random permutation of `0..n`.
+    pub fn permutation(&mut self, n: usize) -> Vec<usize> {
+        let mut result: Vec<usize> = (0..n).collect();
+        // Fisher-Yates: each position only swaps with itself or an earlier slot.
+        for i in (1..n).rev() {
+            result.swap(i, self.next_in_range(0..i + 1));
+        }
+        result
+    }
+
+    /// Generate a uniformly random permutation of `0..n`, decomposed into its
+    /// disjoint cycles. Each cycle lists `i, p(i), p(p(i)), ...`, starting from
+    /// its smallest element, and the cycles are ordered by that element.
+    pub fn random_permutation_cycles(&mut self, n: usize) -> Vec<Vec<usize>> {
+        let perm = self.permutation(n);
+        let mut visited = vec![false; n];
+        let mut cycles = Vec::new();
+        for start in 0..n {
+            if visited[start] {
+                continue;
+            }
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                cycle.push(i);
+                i = perm[i];
+            }
+            cycles.push(cycle);
+        }
+        cycles
+    }
+
+    /// Generate a 2D rotation matrix with a uniformly random angle.
+    #[cfg(feature = "glam")]
+    pub fn next_rotation2(&mut self) -> Mat2 {
+        Mat2::from_angle(self.next_in_range(0.0..std::f32::consts::TAU))
+    }
+
+    /// Generate a uniformly random 3D rotation matrix,
+    /// from a uniform unit quaternion (Shoemake's method).
+    #[cfg(feature = "glam")]
+    pub fn next_rotation3(&mut self) -> Mat3 {
+        use std::f32::consts::TAU;
+        let u1 = self.next::<f32>();
+        let (s2, c2) = (TAU * self.next::<f32>()).sin_cos();
+        let (s3, c3) = (TAU * self.next::<f32>()).sin_cos();
+        let (a, b) = ((1.0 - u1).sqrt(), u1.sqrt());
+        Mat3::from_quat(Quat::from_xyzw(a * s2, a * c2, b * s3, b * c3).normalize())
+    }
+
+    /// Fill `out` with unit vectors uniformly distributed over the sphere,
+    /// e.g. for initial particle velocities. Draws a uniform height and
+    /// angle per vector, which is uniform on the sphere by Archimedes' theorem.
+    #[cfg(feature = "glam")]
+    pub fn fill_directions3(&mut self, out: &mut [Vec3]) {
+        for v in out {
+            let z = self.next_in_range(-1.0..1.0f32);
+            let (s, c) = self.next_in_range(0.0..std::f32::consts::TAU).sin_cos();
+            let r = (1.0 - z * z).sqrt();
+            *v = Vec3::new(r * c, r * s, z);
+        }
+    }
+
+    /// Generate a 2D affine transform with a uniform scale, rotation (in radians),
+    /// and translation each drawn from its range. The scale is applied first,
+    /// then the rotation, then the translation.
+    #[cfg(feature = "glam")]
+    pub fn next_affine2(&mut self, scale: Range<f32>, rotation: Range<f32>, translation: Range<Vec2>) -> Affine2 {
+        let scale = self.next_in_range(scale);
+        let angle = self.next_in_range(rotation);
+        let x = self.next_in_range(translation.start.x..translation.end.x);
+        let y = self.next_in_range(translation.start.y..translation.end.y);
+        Affine2::from_scale_angle_translation(Vec2::splat(scale), angle, Vec2::new(x, y))
+    }
+
+    /// Generate a point uniformly distributed over the area of a 2D triangle.
+    #[cfg(feature = "glam")]
+    pub fn in_triangle(&mut self, a: Vec2, b: Vec2, c: Vec2) -> Vec2 {
+        let [wa, wb, wc] = self.triangle_weights();
+        a * wa + b * wb + c * wc
+    }
+
+    /// Generate a point uniformly distributed over the area of a 3D triangle.
+    #[cfg(feature = "glam")]
+    pub fn in_triangle3(&mut self, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+        let [wa, wb, wc] = self.triangle_weights();
+        a * wa + b * wb + c * wc
+    }
+
+    /// Sample `count` points uniformly over the surface of a triangle mesh.
+    /// Triangles are chosen weighted by area, then sampled uniformly.
+    /// 
+    /// Returns no points if the mesh has no area. Panics if `indices` is not
+    /// a multiple of 3, or an index is out of bounds of `vertices`.
+    #[cfg(feature = "glam")]
+    pub fn sample_mesh(&mut self, vertices: &[Vec3], indices: &[u32], count: usize) -> Vec<Vec3> {
+        assert!(indices.len().is_multiple_of(3), "sample_mesh indices must be a multiple of 3");
+        assert!(
+            indices.iter().all(|&i| (i as usize) < vertices.len()),
+            "sample_mesh index out of bounds"
+        );
+
+        let triangles: Vec<[Vec3; 3]> = indices
+            .chunks_exact(3)
+            .map(|t| [vertices[t[0] as usize], vertices[t[1] as usize], vertices[t[2] as usize]])
+            .collect();
+        let mut cumulative = Vec::with_capacity(triangles.len());
+        let mut total = 0.0;
+        for [a, b, c] in &triangles {
+            total += (*b - *a).cross(*c - *a).length() as f64 * 0.5;
+            cumulative.push(total);
+        }
+
+        let mut points = Vec::with_capacity(count);
+        for _ in 0..count {
+            let Some(i) = self.choose_weighted_index_cached(&cumulative) else { break };
+            let [a, b, c] = triangles[i];
+            points.push(self.in_triangle3(a, b, c));
+        }
+        points
+    }
+
+    /// Barycentric weights of a point uniform over a triangle's area.
+    /// Taking the square root of one draw avoids the clustering toward
+    /// vertex `a` that naive barycentric sampling produces.
+    #[cfg(feature = "glam")]
+    fn triangle_weights(&mut self) -> [f32; 3] {
+        let r1 = self.next::<f32>().sqrt();
+        let r2 = self.next::<f32>();
+        [1.0 - r1, r1 * (1.0 - r2), r1 * r2]
+    }
+
+    /// Remove and return a uniformly random element in O(1), using `swap_remove`.
+    /// This does not preserve the order of the remaining elements.
+    /// Returns `None` if the Vec is empty.
+    pub fn swap_remove_random<T>(&mut self, vec: &mut Vec<T>) -> Option<T> {
+        if vec.is_empty() {
+            return None;
+        }
+        let i = self.next_in_range(0..vec.len());
+        Some(vec.swap_remove(i))
+    }
+
+    /// Choose `K` distinct elements of the slice in random order, without allocating.
+    /// Returns `None` if the slice has fewer than `K` elements.
+    pub fn choose_array<T: Copy, const K: usize>(&mut self, slice: &[T]) -> Option<[T; K]> {
+        let len = slice.len();
+        if len < K {
+            return None;
+        }
+        // Floyd's algorithm picks K distinct indices in O(K^2)
+        // with no scratch space beyond the output.
+        let mut indices = [0usize; K];
+        for (k, j) in (len - K..len).enumerate() {
+            let t = self.next_in_range(0..j + 1);
+            indices[k] = if indices[..k].contains(&t) { j } else { t };
+        }
+        // Floyd's picks a uniform set, shuffle it for a uniform order.
+        for i in (1..K).rev() {
+            indices.swap(i, self.next_in_range(0..i + 1));
+        }
+        Some(indices.map(|i| slice[i]))
+    }
+
+    /// Choose an index with probability proportional to its weight.
+    /// Returns `None` if the weights are empty, any weight is negative
+    /// or NaN, or the total weight is not positive and finite.
+    pub fn weighted_index(&mut self, weights: &[f64]) -> Option<usize> {
+        if weights.iter().any(|w| w.is_nan() || *w < 0.0) {
+            return None;
+        }
+        let total: f64 = weights.iter().sum();
+        if !(total > 0.0 && total.is_finite()) {
+            return None;
+        }
+        let target = self.next::<f64>() * total;
+        let mut sum = 0.0;
+        let mut last = 0;
+        for (i, &w) in weights.iter().enumerate() {
+            sum += w;
+            if sum > target {
+                return Some(i);
+            }
+            if w > 0.0 {
+                last = i;
+            }
+        }
+        // target can equal the total, pick the last index with weight.
+        Some(last)
+    }
+
+    /// Choose an item with probability proportional to `weight_fn(item)`,
+    /// without building a separate weights slice. Returns `None` under
+    /// the same conditions as `weighted_index`, e.g. a total weight of 0.
+    pub fn choose_by_weight<'a, T>(&mut self, items: &'a [T], weight_fn: impl Fn(&T) -> f64) -> Option<&'a T> {
+        let weights: Vec<f64> = items.iter().map(weight_fn).collect();
+        self.weighted_index(&weights).map(|i| &items[i])
+    }
+
+    /// Choose an index using precomputed cumulative weights, so repeated draws
+    /// from the same distribution don't recompute the prefix sum.
+    /// 
+    /// `cumulative[i]` must be the sum of weights `0..=i`, so the slice is
+    /// nondecreasing and ends at the total weight. For the same seed, this
+    /// returns the same index as `weighted_index` on the original weights.
+    /// Returns `None` if the total weight is not positive and finite.
+    pub fn choose_weighted_index_cached(&mut self, cumulative: &[f64]) -> Option<usize> {
+        debug_assert!(
+            cumulative.windows(2).all(|w| w[0] <= w[1]),
+            "cumulative weights must be nondecreasing"
+        );
+        let total = *cumulative.last()?;
+        if !(total > 0.0 && total.is_finite()) {
+            return None;
+        }
+        let target = self.next::<f64>() * total;
+        match cumulative.partition_point(|&c| c <= target) {
+            // target can equal the total, pick the first index reaching it.
+            i if i == cumulative.len() => Some(cumulative.partition_point(|&c| c < total)),
+            i => Some(i),
+        }
+    }
+
+    /// Generate a date and time within the range, with nanosecond resolution.
+    /// Returns `range.start` if the range is empty.
+    #[cfg(feature = "chrono")]
+    pub fn next_datetime(&mut self, range: Range<DateTime<Utc>>) -> DateTime<Utc> {
+        let to_nanos = |t: &DateTime<Utc>| {
+            t.timestamp() as i128 * 1_000_000_000 + t.timestamp_subsec_nanos() as i128
+        };
+        let start = to_nanos(&range.start);
+        let span = to_nanos(&range.end) - start;
+        if span <= 0 {
+            return range.start;
+        }
+        let v = (self.next::<u64>() as u128) << 64 | self.next::<u64>() as u128;
+        let nanos = start + (v % span as u128) as i128;
+        DateTime::from_timestamp(
+            nanos.div_euclid(1_000_000_000) as i64,
+            nanos.rem_euclid(1_000_000_000) as u32,
+        )
+        .expect("timestamp between two valid datetimes is valid")
+    }
+
+    /// Generate a row-major `width x height` occupancy grid where each cell
+    /// is `true` with probability `fill_prob` (clamped to [0, 1]).
+    /// A common starting point for cellular automata cave generation.
+    pub fn bool_grid(&mut self, width: usize, height: usize, fill_prob: f64) -> Vec<bool> {
+        let len = width
+            .checked_mul(height)
+            .expect("bool_grid dimensions overflow usize");
+        let p = fill_prob.clamp(0.0, 1.0);
+        (0..len).map(|_| self.next::<f64>() < p).collect()
+    }
+
+    /// Generate a row-major `width x height` tile map where each cell is
+    /// independently a tile id from `tiles`, chosen with probability
+    /// proportional to its weight.
+    ///
+    /// Builds an alias table once, so each cell costs O(1) regardless of
+    /// the number of tiles. Panics if `tiles` is empty, any weight is
+    /// negative or NaN, or the total weight is not positive and finite.
+    pub fn weighted_tilemap(&mut self, width: usize, height: usize, tiles: &[(u8, f64)]) -> Vec<u8> {
+        let len = width
+            .checked_mul(height)
+            .expect("weighted_tilemap dimensions overflow usize");
+        assert!(
+            tiles.iter().all(|(_, w)| *w >= 0.0),
+            "weighted_tilemap weights must be non-negative"
+        );
+        let total: f64 = tiles.iter().map(|(_, w)| w).sum();
+        assert!(
+            total > 0.0 && total.is_finite(),
+            "weighted_tilemap requires a positive, finite total weight"
+        );
+
+        // Vose's alias method: each column keeps its own tile with
+        // probability `prob[i]`, and otherwise gives `alias[i]`.
+        let n = tiles.len();
+        let mut prob: Vec<f64> = tiles.iter().map(|(_, w)| w * n as f64 / total).collect();
+        let mut alias: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..n).partition(|&i| prob[i] < 1.0);
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            alias[s] = l;
+            prob[l] -= 1.0 - prob[s];
+            if prob[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // whatever is left over is 1 up to rounding error
+        for i in small.into_iter().chain(large) {
+            prob[i] = 1.0;
+        }
+
+        (0..len)
+            .map(|_| {
+                let i = self.next_in_range(0..n);
+                let i = if self.next::<f64>() < prob[i] { i } else { alias[i] };
+                tiles[i].0
+            })
+            .collect()
+    }
+
+    /// Generate a mask of length `n` with exactly `m` `true` values
+    /// at uniformly random positions.
+    /// 
+    /// Panics if `m > n`.
+    pub fn pick_exactly(&mut self, n: usize, m: usize) -> Vec<bool> {
+        assert!(m <= n, "pick_exactly: cannot pick {m} of {n}");
+        let mut indices: Vec<usize> = (0..n).collect();
+        let mut mask = vec![false; n];
+        // partial Fisher-Yates, only the first m positions are needed.
+        for i in 0..m {
+            indices.swap(i, self.next_in_range(i..n));
+            mask[indices[i]] = true;
+        }
+        mask
+    }
+
+    /// Add zero-mean noise to each value while keeping the total sum unchanged
+    /// (up to float rounding). Each value moves by at most `2 * amount`.
+    pub fn jitter_preserving_sum(&mut self, values: &mut [f64], amount: f64) {
+        if values.is_empty() {
+            return;
+        }
+        let mut offsets: Vec<f64> = values
+            .iter()
+            .map(|_| self.next_in_range(-amount..amount))
+            .collect();
+        // center the offsets so they sum to zero.
+        let mean = offsets.iter().sum::<f64>() / offsets.len() as f64;
+        offsets.iter_mut().for_each(|o| *o -= mean);
+        for (v, o) in values.iter_mut().zip(offsets) {
+            *v += o;
+        }
+    }
+
+    /// Generate an undirected Erdős–Rényi random graph on `n` nodes, where each
+    /// of the `n * (n - 1) / 2` possible edges is included with probability `p`.
+    /// Edges are returned as `(i, j)` with `i < j`, so there are no self-loops
+    /// or duplicate edges.
+    pub fn erdos_renyi(&mut self, n: usize, p: f64) -> Vec<(usize, usize)> {
+        let p = p.clamp(0.0, 1.0);
+        let mut edges = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                if self.next::<f64>() < p {
+                    edges.push((i, j));
+                }
+            }
+        }
+        edges
+    }
+
+    /// Generate the row-major `n x n` adjacency matrix of an undirected
+    /// random graph, where each pair of distinct vertices is joined with
+    /// probability `p`. The matrix is symmetric with a false diagonal.
+    pub fn symmetric_bool_matrix(&mut self, n: usize, p: f64) -> Vec<bool> {
+        let len = n.checked_mul(n).expect("symmetric_bool_matrix size overflows usize");
+        let p = p.clamp(0.0, 1.0);
+        let mut result = vec![false; len];
+        for i in 0..n {
+            for j in i + 1..n {
+                let edge = self.next::<f64>() < p;
+                result[i * n + j] = edge;
+                result[j * n + i] = edge;
+            }
+        }
+        result
+    }
+
+    /// Generate `total` bools made of alternating runs of `true` and `false`,
+    /// with run lengths geometrically distributed with mean `mean_run`
+    /// (at least 1). The final run is cut short to fit `total`.
+    pub fn next_runs(&mut self, total: usize, mean_run: f64) -> Vec<bool> {
+        let q = 1.0 / mean_run.max(1.0);
+        let mut result = Vec::with_capacity(total);
+        let mut value = self.coin();
+        while result.len() < total {
+            let len = if q >= 1.0 {
+                1
+            } else {
+                // inverse CDF of the geometric distribution on 1, 2, ...
+                1 + (self.next_open_unit().ln() / (1.0 - q).ln()) as usize
+            };
+            let len = len.min(total - result.len());
+            result.extend(std::iter::repeat_n(value, len));
+            value = !value;
+        }
+        result
+    }
+
+    /// Choose an item from a fixed-size array with integer weights, using only
+    /// integer math and no allocation.
+    /// 
+    /// Panics if the weights sum to 0.
+    pub fn choose_static<T: Copy, const N: usize>(&mut self, items: &[T; N], weights: &[u32; N]) -> T {
+        let total: u64 = weights.iter().map(|&w| w as u64).sum();
+        assert!(total > 0, "choose_static requires a positive total weight");
+        let mut k = self.next_in_range(0..total);
+        for (item, &w) in items.iter().zip(weights) {
+            match k.checked_sub(w as u64) {
+                Some(rest) => k = rest,
+                None => return *item,
+            }
+        }
+        unreachable!("k is less than the total weight")
+    }
+
+    /// Choose an index from a precomputed CDF, where `cdf[i]` is the probability
+    /// of choosing an index `<= i`. The slice must be nondecreasing and end at 1.0.
+    /// 
+    /// Panics if `cdf` is empty.
+    pub fn sample_cdf(&mut self, cdf: &[f64]) -> usize {
+        assert!(!cdf.is_empty(), "sample_cdf requires a non-empty cdf");
+        debug_assert!(
+            cdf.windows(2).all(|w| w[0] <= w[1]),
+            "cdf must be nondecreasing"
+        );
+        let u = self.next_f64_full();
+        cdf.partition_point(|&c| c <= u).min(cdf.len() - 1)
+    }
+
+    /// Generate a palette of `count` RGB colors. Hues start at a random angle
+    /// and step by the golden ratio, so they stay well spread for any count,
+    /// with saturation and value jittered slightly for variety.
+    pub fn next_palette(&mut self, count: usize) -> Vec<[u8; 3]> {
+        const GOLDEN_RATIO_CONJUGATE: f64 = 0.618_033_988_749_895;
+        let mut hue = self.next_f64_full();
+        (0..count)
+            .map(|_| {
+                let s = self.next_in_range(0.5..0.8);
+                let v = self.next_in_range(0.75..0.95);
+                let rgb = hsv_to_rgb(hue, s, v);
+                hue = (hue + GOLDEN_RATIO_CONJUGATE).fract();
+                rgb
+            })
+            .collect()
+    }
+
+    /// Generate a valid UTF-8 string of exactly `byte_len` bytes, for fuzzing
+    /// text handling. Each char's encoded width (1 to 4 bytes) is chosen
+    /// uniformly among those that still fit, then a random char of that width.
+    pub fn random_utf8(&mut self, byte_len: usize) -> String {
+        let mut result = String::with_capacity(byte_len);
+        while result.len() < byte_len {
+            let width = self.next_in_range(1..(byte_len - result.len()).min(4) + 1);
+            let c = match width {
+                1 => self.next_in_range(0..0x80u32),
+                2 => self.next_in_range(0x80..0x800u32),
+                // skip the surrogate range, which isn't valid in UTF-8
+                3 => self.next_in_range_excluding_span(0x800..0x10000u32, 0xD800..0xE000),
+                _ => self.next_in_range(0x10000..0x110000u32),
+            };
+            result.push(char::from_u32(c).expect("generated a valid scalar value"));
+        }
+        result
+    }
+
+    /// Draw from `range` with the sub-range `skip` removed, by drawing
+    /// from the reduced count and stepping over `skip`.
+    fn next_in_range_excluding_span(&mut self, range: Range<u32>, skip: Range<u32>) -> u32 {
+        let v = self.next_in_range(range.start..range.end - (skip.end - skip.start));
+        if v >= skip.start { v + (skip.end - skip.start) } else { v }
+    }
+
+    /// Randomly upper or lowercase each ASCII letter in the string.
+    /// All other characters pass through unchanged.
+    pub fn jitter_case(&mut self, s: &str) -> String {
+        s.chars()
+            .map(|c| match c.is_ascii_alphabetic() {
+                true if self.coin() => c.to_ascii_uppercase(),
+                true => c.to_ascii_lowercase(),
+                false => c,
+            })
+            .collect()
+    }
+
+    /// Pair each person with someone to give to, so that nobody gets themselves
+    /// and the assignment forms one ring through everyone. Returns
+    /// `(giver, receiver)` pairs in the order of `people`. Uses a random
+    /// single-cycle permutation from Sattolo's algorithm.
+    /// 
+    /// A single person is paired with themselves, as there is nobody else.
+    pub fn assign_cyclic<'a, T>(&mut self, people: &'a [T]) -> Vec<(&'a T, &'a T)> {
+        let mut receivers: Vec<usize> = (0..people.len()).collect();
+        self.shuffle_with(&mut receivers, ShuffleAlgo::Sattolo);
+        people
+            .iter()
+            .zip(receivers)
+            .map(|(giver, r)| (giver, &people[r]))
+            .collect()
+    }
+
+    /// Generate a uniformly random derangement of `0..n`: a permutation where
+    /// no index maps to itself, e.g. for secret-santa assignments.
+    /// 
+    /// Uses Fisher-Yates with early refusal, restarting as soon as a position
+    /// is finalized as a fixed point. Returns `None` if `n == 1`, which has no
+    /// derangement, or if the rejection cap is reached.
+    pub fn derangement(&mut self, n: usize) -> Option<Vec<usize>> {
+        if n == 1 {
+            return None;
+        }
+        let mut result = Vec::with_capacity(n);
+        self.retry(|rng| {
+            result.clear();
+            result.extend(0..n);
+            for i in (1..n).rev() {
+                result.swap(i, rng.next_in_range(0..i + 1));
+                if result[i] == i {
+                    return None;
+                }
+            }
+            (n == 0 || result[0] != 0).then(|| result.clone())
+        })
+        .ok()
+    }
+
+    /// Generate a uniformly random permutation of `0..N` as an array,
+    /// for small lookup tables. `N` must be at most 256.
+    pub fn permutation_array<const N: usize>(&mut self) -> [u8; N] {
+        const { assert!(N <= 256, "permutation_array requires N <= 256") };
+        let mut result = std::array::from_fn(|i| i as u8);
+        self.shuffle_range(&mut result, 0..N);
+        result
+    }
+
+    /// Generate a bool that tends to repeat the previous output, for
+    /// slightly clustered randomness. See `StickyBool` for the model.
+    pub fn next_bool_sticky(&mut self, sticky: &mut StickyBool) -> bool {
+        let value = match sticky.last {
+            Some(last) if self.next::<f64>() < sticky.stickiness => last,
+            _ => self.next::<f64>() < sticky.p,
+        };
+        sticky.last = Some(value);
+        value
+    }
+
+    /// Generate `len` bools from a first-order Markov chain, where each value
+    /// is `true` with marginal probability `p` and adjacent values have a
+    /// correlation coefficient of `correlation` (clamped to [0, 1]).
+    /// Uses the `StickyBool` model with `stickiness = correlation`.
+    pub fn correlated_bools(&mut self, len: usize, p: f64, correlation: f64) -> Vec<bool> {
+        let mut sticky = StickyBool::new(p, correlation);
+        (0..len).map(|_| self.next_bool_sticky(&mut sticky)).collect()
+    }
+
+    /// Stratified sampling of the unit square. Divides it into `nx * ny` cells
+    /// and places one jittered sample in each, in row-major order. This
+    /// covers the square more evenly than independent random points.
+    #[cfg(feature = "glam")]
+    pub fn stratified_2d(&mut self, nx: usize, ny: usize) -> Vec<Vec2> {
+        // a point in [k / n, (k + 1) / n), clamped since
+        // `k + u` can round up to `k + 1` in f32
+        let jitter = |rng: &mut Self, k: usize, n: usize| {
+            let u = unit_f32(rng.next());
+            let end = (k + 1) as f32 / n as f32;
+            ((k as f32 + u) / n as f32).min(end.next_down())
+        };
+        let mut result = Vec::with_capacity(nx * ny);
+        for j in 0..ny {
+            for i in 0..nx {
+                let x = jitter(self, i, nx);
+                let y = jitter(self, j, ny);
+                result.push(Vec2::new(x, y));
+            }
+        }
+        result
+    }
+}
+
+/// Shuffle algorithms for `WyRand::shuffle_with`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ShuffleAlgo {
+    /// Every permutation is equally likely.
+    #[default]
+    FisherYates,
+    /// Every single-cycle permutation is equally likely, so for slices of
+    /// length 2 or more no element stays in place.
+    Sattolo,
+}
+
+/// How `WyRand::next_in_wrapping_range` treats a band that
+/// extends past the bounds of the type.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BandMode {
+    /// Clip the band to the type's range.
+    #[default]
+    Saturate,
+    /// Wrap the band around to the other end of the type's range.
+    Wrap,
+}
+
+/// Retained state for `WyRand::next_bool_sticky`.
+/// 
+/// Each draw repeats the previous output with probability `stickiness`,
+/// and otherwise is a fresh draw that is `true` with probability `p`.
+/// Because fresh draws have rate `p`, the long-run fraction of `true`
+/// outputs is still `p`, but runs are longer on average: the expected
+/// run length grows as `stickiness` approaches 1.
+#[derive(Copy, Clone, Debug)]
+pub struct StickyBool {
+    p: f64,
+    stickiness: f64,
+    last: Option<bool>,
+}
+
+impl StickyBool {
+    /// Construct the state. `p` and `stickiness` are clamped to [0, 1].
+    pub fn new(p: f64, stickiness: f64) -> Self {
+        Self {
+            p: p.clamp(0.0, 1.0),
+            stickiness: stickiness.clamp(0.0, 1.0),
+            last: None,
+        }
+    }
+}
+
+/// Hash a value to a u64 without constructing an RNG.
+/// 
+/// This is WyRand's mixing step followed by a second round. One round
+/// alone leaves the outputs for consecutive inputs visibly correlated,
+/// which matters when hashing coordinates or indices.
+pub fn mix(x: u64) -> u64 {
+    let x = x.wrapping_add(P0);
+    let y = mum(x, x ^ P1);
+    mum(y ^ P0, y ^ P2)
+}
+
+/// Hash two values to a u64, e.g. a pair of coordinates.
+/// The order of the arguments matters.
+pub fn mix2(a: u64, b: u64) -> u64 {
+    mix(mix(a) ^ b)
+}
+
+/// The first `n` u64 outputs of `WyRand::with_seed(seed)`.
+/// 
+/// Downstream crates can pin these to verify they get the same sequence
+/// across versions. The sequence for a given seed is a stability guarantee:
+/// changing it requires a major version bump.
+pub fn golden_sequence(seed: u64, n: usize) -> Vec<u64> {
+    let mut rng = WyRand::with_seed(seed);
+    (0..n).map(|_| rng.next()).collect()
+}
+
+/// Map the top 52 bits of `v` into (0, 1), offset by half a step.
+fn open_unit(v: u64) -> f64 {
+    ((v >> 12) as f64 + 0.5) / (1u64 << 52) as f64
+}
+
+/// Map the top 24 bits of `v` into [0, 1), which fill the f32 mantissa.
+fn unit_f32(v: u64) -> f32 {
+    (v >> 40) as f32 * (1.0 / (1u32 << 24) as f32)
+}
+
+/// Convert a color from HSV, each in [0, 1], to RGB bytes.
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> [u8; 3] {
+    let h6 = h.fract() * 6.0;
+    let f = h6.fract();
+    let (p, q, t) = (v * (1.0 - s), v * (1.0 - s * f), v * (1.0 - s * (1.0 - f)));
+    let (r, g, b) = match h6 as u32 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    };
+    [(r * 255.0).round() as u8, (g * 255.0).round() as u8, (b * 255.0).round() as u8]
+}
+
+/// Multiply into 128 bits and fold the halves together.
+#[inline]
+fn mum(a: u64, b: u64) -> u64 {
+    let r = u128::from(a).wrapping_mul(u128::from(b));
+    (r.wrapping_shr(64) ^ r) as u64
+}
+
+impl Default for WyRand {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub trait FromRng {
@@ -121,6 +1270,12 @@ impl FromRng for i8 {
     }
 }
 
+impl FromRng for bool {
+    fn from_rng(v: u64) -> Self {
+        (v >> 63) == 1
+    }
+}
+
 impl FromRng for f64 {
     fn from_rng(v: u64) -> Self {
         v as f64 / u64::MAX as f64
@@ -204,6 +1359,79 @@ impl FromRng for UVec4 {
     }
 }
 
+/// A range that values can be sampled from with `next_in_range`.
+/// 
+/// This is sealed, and implemented for `Range<T>` of every `RangeRng` type,
+/// plus `RangeFrom<T>` and `RangeTo<T>` of the integer types.
+pub trait SampleRange<T>: sealed::Sealed {
+    fn sample(self, v: u64) -> T;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+impl<T: RangeRng> sealed::Sealed for Range<T> {}
+
+impl<T: RangeRng> SampleRange<T> for Range<T> {
+    fn sample(self, v: u64) -> T {
+        T::from_range(v, self)
+    }
+}
+
+/// `RangeFrom` impls for types as wide as the rng output,
+/// where `start..` can cover all 2^64 values.
+macro_rules! impl_range_from_wide {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for RangeFrom<$t> {}
+
+        impl SampleRange<$t> for RangeFrom<$t> {
+            fn sample(self, v: u64) -> $t {
+                // number of values in start..=MAX, 0 if it covers all 2^64 values.
+                let span = (<$t>::MAX as u64).wrapping_sub(self.start as u64).wrapping_add(1);
+                if span == 0 {
+                    v as $t
+                } else {
+                    self.start.wrapping_add((v % span) as $t)
+                }
+            }
+        }
+    )*};
+}
+
+/// `RangeFrom` impls for types narrower than the rng output.
+macro_rules! impl_range_from_narrow {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for RangeFrom<$t> {}
+
+        impl SampleRange<$t> for RangeFrom<$t> {
+            fn sample(self, v: u64) -> $t {
+                // number of values in start..=MAX
+                let span = (<$t>::MAX as u64).wrapping_sub(self.start as u64).wrapping_add(1);
+                self.start.wrapping_add((v % span) as $t)
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_range_to {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for RangeTo<$t> {}
+
+        impl SampleRange<$t> for RangeTo<$t> {
+            fn sample(self, v: u64) -> $t {
+                // number of values in MIN..end
+                let span = (self.end as u64).wrapping_sub(<$t>::MIN as u64);
+                <$t>::MIN.wrapping_add((v % span) as $t)
+            }
+        }
+    )*};
+}
+
+impl_range_from_wide!(u64, i64, usize, isize);
+impl_range_from_narrow!(u32, i32, u16, i16, u8, i8);
+impl_range_to!(u64, i64, usize, isize, u32, i32, u16, i16, u8, i8);
+
 pub trait RangeRng: Sized {
     fn from_range(v: u64, range: Range<Self>) -> Self;
 }
@@ -270,16 +1498,43 @@ impl RangeRng for i8 {
 
 impl RangeRng for f64 {
     fn from_range(v: u64, range: Range<Self>) -> Self {
-        range.start + (v as f64 / u64::MAX as f64) * (range.end - range.start)
+        f64::lerp_half_open(v as f64 / u64::MAX as f64, range)
     }
 }
 
 impl RangeRng for f32 {
     fn from_range(v: u64, range: Range<Self>) -> Self {
-        range.start + (v as f64 / u64::MAX as f64) as f32 * (range.end - range.start)
+        f32::lerp_half_open((v as f64 / u64::MAX as f64) as f32, range)
     }
 }
 
+/// Float interpolation that keeps the end of the range exclusive.
+trait HalfOpenLerp: Sized {
+    /// Map `u` in [0, 1] into `[range.start, range.end)`. Returns
+    /// `range.start` if the range is empty or reversed.
+    fn lerp_half_open(u: Self, range: Range<Self>) -> Self;
+}
+
+macro_rules! impl_half_open_lerp {
+    ($($t:ty),*) => {$(
+        impl HalfOpenLerp for $t {
+            fn lerp_half_open(u: Self, range: Range<Self>) -> Self {
+                let span = range.end - range.start;
+                // fall back to the lerp form when `end - start` overflows.
+                let x = if span.is_finite() {
+                    range.start + u * span
+                } else {
+                    range.start * (1.0 - u) + range.end * u
+                };
+                // `u` can be 1 and the math can round up, keep the end exclusive.
+                x.min(range.end.next_down()).max(range.start)
+            }
+        }
+    )*};
+}
+
+impl_half_open_lerp!(f32, f64);
+
 #[cfg(feature = "glam")]
 impl RangeRng for IVec2 {
     fn from_range(v: u64, range: Range<Self>) -> Self {
@@ -349,3 +1604,1132 @@ impl RangeRng for UVec4 {
         }
     }
 }
+
+/// Integer types that can be stepped by one, for methods that remap draws.
+pub trait StepRng: RangeRng + Copy + PartialOrd {
+    /// The value one greater than `self`.
+    fn forward(self) -> Self;
+    /// The value one less than `self`.
+    fn backward(self) -> Self;
+}
+
+macro_rules! impl_step_rng {
+    ($($t:ty),*) => {$(
+        impl StepRng for $t {
+            fn forward(self) -> Self {
+                self + 1
+            }
+
+            fn backward(self) -> Self {
+                self - 1
+            }
+        }
+    )*};
+}
+
+impl_step_rng!(u64, i64, usize, isize, u32, i32, u16, i16, u8, i8);
+
+/// Integer types for `WyRand::next_in_wrapping_range`, which
+/// does its band arithmetic in `i128` so it can't overflow.
+pub trait WrapRng: Copy {
+    /// The smallest value of the type.
+    const MIN: i128;
+    /// The largest value of the type.
+    const MAX: i128;
+    fn to_i128(self) -> i128;
+    /// Truncate to the type, wrapping values outside its range.
+    fn from_i128_wrapping(v: i128) -> Self;
+}
+
+macro_rules! impl_wrap_rng {
+    ($($t:ty),*) => {$(
+        impl WrapRng for $t {
+            const MIN: i128 = <$t>::MIN as i128;
+            const MAX: i128 = <$t>::MAX as i128;
+
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn from_i128_wrapping(v: i128) -> Self {
+                v as $t
+            }
+        }
+    )*};
+}
+
+impl_wrap_rng!(u64, i64, usize, isize, u32, i32, u16, i16, u8, i8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutation_is_valid_and_uniform() {
+        let mut rng = WyRand::with_seed(0);
+        let mut sorted = rng.permutation(100);
+        sorted.sort();
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..60_000 {
+            *counts.entry(rng.permutation(3)).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 6);
+        for &count in counts.values() {
+            assert!((9_500..10_500).contains(&count), "ordering count {count}");
+        }
+    }
+
+    #[test]
+    fn float_ranges_are_finite_and_in_range() {
+        let mut rng = WyRand::with_seed(0);
+        for _ in 0..10_000 {
+            let x = rng.next_in_range(-1e30..1e30f64);
+            assert!(x.is_finite() && (-1e30..1e30).contains(&x));
+            let x = rng.next_in_range(f64::MIN..f64::MAX);
+            assert!(x.is_finite() && x < f64::MAX);
+            let x = rng.next_in_range(-1e30..1e30f32);
+            assert!(x.is_finite() && (-1e30..1e30).contains(&x));
+            let x = rng.next_in_range(f32::MIN..f32::MAX);
+            assert!(x.is_finite() && x < f32::MAX);
+        }
+        // the largest draw still lands below the end
+        assert!(f64::from_range(u64::MAX, 1.0..2.0) < 2.0);
+        assert!(f32::from_range(u64::MAX, 1.0..2.0) < 2.0);
+    }
+
+    #[test]
+    fn open_ended_ranges_respect_bounds() {
+        let mut rng = WyRand::with_seed(0);
+        let mut seen_from = [false; 256];
+        let mut seen_to = [false; 256];
+        for _ in 0..10_000 {
+            let x = rng.next_in_range(5u8..);
+            assert!(x >= 5);
+            seen_from[x as usize] = true;
+            let x = rng.next_in_range(..10u8);
+            assert!(x < 10);
+            seen_to[x as usize] = true;
+            assert!(rng.next_in_range(-3i8..) >= -3);
+            assert!(rng.next_in_range(..-3i32) < -3);
+        }
+        assert!(seen_from[5] && seen_from[255]);
+        assert!(seen_to[0] && seen_to[9]);
+        // `0..` covers every u64, so any output is accepted
+        let _ = rng.next_in_range(0u64..);
+    }
+
+    #[test]
+    fn sticky_bools_keep_rate_with_longer_runs() {
+        let mut rng = WyRand::with_seed(0);
+        let mut sticky = StickyBool::new(0.3, 0.8);
+        let values: Vec<bool> = (0..200_000).map(|_| rng.next_bool_sticky(&mut sticky)).collect();
+        let rate = values.iter().filter(|&&b| b).count() as f64 / values.len() as f64;
+        assert!((rate - 0.3).abs() < 0.02, "marginal rate {rate}");
+
+        // a run of trues continues with probability s + (1 - s) p,
+        // so its mean length is 1 / ((1 - s)(1 - p)) = 1 / 0.14
+        let runs = values.windows(2).filter(|w| w[0] && !w[1]).count();
+        let mean_run = values.iter().filter(|&&b| b).count() as f64 / runs as f64;
+        assert!((mean_run - 1.0 / 0.14).abs() < 0.5, "mean run {mean_run}");
+    }
+
+    #[test]
+    fn mix_outputs_are_pinned() {
+        assert_eq!(mix(0), 1401588328420136497);
+        assert_eq!(mix(1), 2782548638580629617);
+        assert_eq!(mix2(1, 2), 16728182761735822159);
+        assert_eq!(mix2(2, 1), 11947021465220085764);
+    }
+
+    #[test]
+    fn mix_avalanches() {
+        let mut total = 0u64;
+        let mut trials = 0u64;
+        for x in 0..200u64 {
+            for bit in 0..64 {
+                total += (mix(x) ^ mix(x ^ (1 << bit))).count_ones() as u64;
+                total += (mix2(x, 7) ^ mix2(x, 7 ^ (1 << bit))).count_ones() as u64;
+                trials += 2;
+            }
+        }
+        // each flipped input bit should flip about half the output bits
+        let mean = total as f64 / trials as f64;
+        assert!((mean - 32.0).abs() < 0.5, "mean flipped bits {mean}");
+    }
+
+    #[test]
+    fn mix_decorrelates_consecutive_inputs() {
+        for hash in [mix as fn(u64) -> u64, |n| mix2(7, n)] {
+            let xs: Vec<f64> = (0..10_001).map(|n| hash(n) as f64 / u64::MAX as f64 - 0.5).collect();
+            let cov = xs.windows(2).map(|w| w[0] * w[1]).sum::<f64>() / 10_000.0;
+            let var = xs.iter().map(|x| x * x).sum::<f64>() / 10_001.0;
+            assert!((cov / var).abs() < 0.05, "lag-1 correlation {}", cov / var);
+        }
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn stratified_2d_places_one_sample_per_cell() {
+        let mut rng = WyRand::with_seed(0);
+        let (nx, ny) = (7, 5);
+        for _ in 0..100 {
+            let points = rng.stratified_2d(nx, ny);
+            assert_eq!(points.len(), nx * ny);
+            for (k, p) in points.iter().enumerate() {
+                let (i, j) = (k % nx, k / nx);
+                assert!(p.x >= i as f32 / nx as f32 && p.x < (i + 1) as f32 / nx as f32);
+                assert!(p.y >= j as f32 / ny as f32 && p.y < (j + 1) as f32 / ny as f32);
+            }
+        }
+    }
+
+    #[test]
+    fn swap_remove_random_is_uniform() {
+        let mut rng = WyRand::with_seed(0);
+        let mut counts = [0u32; 5];
+        for _ in 0..50_000 {
+            let mut items = vec![0, 1, 2, 3, 4];
+            let removed = rng.swap_remove_random(&mut items).unwrap();
+            assert_eq!(items.len(), 4);
+            assert!(!items.contains(&removed));
+            counts[removed] += 1;
+        }
+        for count in counts {
+            assert!((9_500..10_500).contains(&count), "count {count}");
+        }
+        assert_eq!(rng.swap_remove_random::<u8>(&mut Vec::new()), None);
+    }
+
+    #[test]
+    fn rejection_cap_stops_impossible_predicates() {
+        let mut rng = WyRand::with_seed(0);
+        rng.set_rejection_cap(10);
+        assert_eq!(rng.rejection_cap(), 10);
+        assert_eq!(rng.try_next_where(|_: &u32| false), Err(RngError::TooManyRejections));
+
+        // it gave up after exactly `cap` draws
+        let mut expected = WyRand::with_seed(0);
+        for _ in 0..10 {
+            expected.next::<u32>();
+        }
+        assert_eq!(rng.to_seed(), expected.to_seed());
+
+        // a constant seed source gives up the same way every time
+        let source = crate::seed::FixedSource(7);
+        let mut a = WyRand::new_with_source(&source);
+        let mut b = WyRand::new_with_source(&source);
+        a.set_rejection_cap(100);
+        b.set_rejection_cap(100);
+        assert_eq!(a.try_next_where(|&v: &u64| v == 0), Err(RngError::TooManyRejections));
+        assert_eq!(b.try_next_where(|&v: &u64| v == 0), Err(RngError::TooManyRejections));
+        assert_eq!(a.draws_since_seed(), 100);
+        assert_eq!(a.to_seed(), b.to_seed());
+    }
+
+    #[test]
+    fn next_in_range_excluding_never_returns_exclude() {
+        let mut rng = WyRand::with_seed(0);
+        let mut seen = [false; 10];
+        for _ in 0..10_000 {
+            let x = rng.next_in_range_excluding(0..10u32, 4);
+            assert_ne!(x, 4);
+            seen[x as usize] = true;
+        }
+        assert_eq!(seen.iter().filter(|&&s| s).count(), 9);
+        // edges of the range
+        for _ in 0..1_000 {
+            assert_ne!(rng.next_in_range_excluding(-5..5i8, -5), -5);
+            assert_ne!(rng.next_in_range_excluding(-5..5i8, 4), 4);
+        }
+    }
+
+    #[test]
+    fn seed_bytes_round_trip() {
+        let mut rng = WyRand::with_seed(0xDEAD_BEEF);
+        rng.next::<u64>();
+        let mut restored = WyRand::from_rng_seed(rng.to_seed());
+        for _ in 0..16 {
+            assert_eq!(rng.next::<u64>(), restored.next::<u64>());
+        }
+        assert_eq!(WyRand::from_rng_seed(7u64.to_le_bytes()).to_seed(), WyRand::with_seed(7).to_seed());
+    }
+
+    #[test]
+    fn flip_n_counts_heads_with_one_draw() {
+        let mut rng = WyRand::with_seed(0);
+        let mut expected = rng;
+        rng.flip_n(64);
+        expected.next::<u64>();
+        assert_eq!(rng.to_seed(), expected.to_seed());
+
+        let total: u64 = (0..10_000).map(|_| rng.flip_n(64) as u64).sum();
+        let mean = total as f64 / 10_000.0;
+        assert!((mean - 32.0).abs() < 0.2, "mean heads {mean}");
+        assert!((0..1000).all(|_| rng.flip_n(10) <= 10));
+
+        let heads = (0..10_000).filter(|_| rng.coin()).count();
+        assert!((4_800..5_200).contains(&heads));
+    }
+
+    #[test]
+    fn golden_sequence_is_stable() {
+        // changing these values is a breaking change, see `golden_sequence`
+        assert_eq!(golden_sequence(0, 8), [
+            1233057930238600590,
+            14892235431655409005,
+            7060326114132480676,
+            8700114197940311904,
+            8935209279347499230,
+            9757495300053101343,
+            16290690066647324713,
+            3000105350103995810,
+        ]);
+    }
+
+    #[test]
+    fn truncated_normal_respects_bounds_in_the_tails() {
+        let mut rng = WyRand::with_seed(0);
+        let cases = [(-1.0, 1.0), (0.5, 0.6), (2.0, 5.0), (10.0, 11.0), (-11.0, -10.0), (3.0, f64::INFINITY)];
+        for (low, high) in cases {
+            let samples: Vec<f64> = (0..20_000).map(|_| rng.next_truncated_normal(0.0, 1.0, low, high)).collect();
+            assert!(samples.iter().all(|x| (low..=high).contains(x)), "out of [{low}, {high}]");
+            let distinct = samples.iter().filter(|&&x| x != low && x != high).count();
+            assert!(distinct > 19_000, "samples pile up on the bounds of [{low}, {high}]");
+        }
+
+        // the density falls off fast, so tail samples concentrate at the inner bound:
+        // E[Z | 10 <= Z <= 11] is about 10.098
+        let mean = (0..20_000).map(|_| rng.next_truncated_normal(0.0, 1.0, 10.0, 11.0)).sum::<f64>() / 20_000.0;
+        assert!((mean - 10.098).abs() < 0.01, "tail mean {mean}");
+        let mean = (0..20_000).map(|_| rng.next_truncated_normal(5.0, 2.0, 3.0, 7.0)).sum::<f64>() / 20_000.0;
+        assert!((mean - 5.0).abs() < 0.05, "central mean {mean}");
+    }
+
+    #[test]
+    fn fixed_source_matches_with_seed() {
+        let mut a = WyRand::new_with_source(&crate::seed::FixedSource(123));
+        let mut b = WyRand::with_seed(123);
+        for _ in 0..8 {
+            assert_eq!(a.next::<u64>(), b.next::<u64>());
+        }
+    }
+
+    #[test]
+    fn choose_array_picks_distinct_elements() {
+        let mut rng = WyRand::with_seed(0);
+        let items = [10, 20, 30, 40, 50, 60];
+        for _ in 0..1_000 {
+            let chosen: [i32; 4] = rng.choose_array(&items).unwrap();
+            for (i, a) in chosen.iter().enumerate() {
+                assert!(items.contains(a));
+                assert!(!chosen[i + 1..].contains(a));
+            }
+        }
+        assert_eq!(rng.choose_array::<i32, 7>(&items), None);
+        assert_eq!(rng.choose_array::<i32, 0>(&[]), Some([]));
+    }
+
+    #[test]
+    fn jitter_case_preserves_non_letters() {
+        let mut rng = WyRand::with_seed(0);
+        let input = "Hello, wörld! 123_abc";
+        let output = rng.jitter_case(input);
+        assert_eq!(output.to_ascii_lowercase(), input.to_ascii_lowercase());
+        for (a, b) in input.chars().zip(output.chars()) {
+            if !a.is_ascii_alphabetic() {
+                assert_eq!(a, b);
+            }
+        }
+        let changed = (0..100).filter(|_| rng.jitter_case("abcdef") != "abcdef").count();
+        assert!(changed > 90);
+    }
+
+    #[test]
+    fn cached_weights_match_uncached() {
+        let weights = [0.5, 0.0, 2.0, 1.25, 0.25];
+        let cumulative: Vec<f64> = weights
+            .iter()
+            .scan(0.0, |sum, w| {
+                *sum += w;
+                Some(*sum)
+            })
+            .collect();
+        let mut a = WyRand::with_seed(0);
+        let mut b = WyRand::with_seed(0);
+        for _ in 0..10_000 {
+            assert_eq!(a.weighted_index(&weights), b.choose_weighted_index_cached(&cumulative));
+        }
+        assert_eq!(a.choose_weighted_index_cached(&[]), None);
+        assert_eq!(a.choose_weighted_index_cached(&[0.0, 0.0]), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn next_datetime_is_in_range_and_deterministic() {
+        let start = DateTime::from_timestamp(-1_000_000, 5).unwrap();
+        let end = DateTime::from_timestamp(2_000_000_000, 0).unwrap();
+        let mut a = WyRand::with_seed(0);
+        let mut b = WyRand::with_seed(0);
+        for _ in 0..10_000 {
+            let t = a.next_datetime(start..end);
+            assert!(t >= start && t < end);
+            assert_eq!(t, b.next_datetime(start..end));
+        }
+        assert_eq!(a.next_datetime(start..start), start);
+        assert_eq!(a.next_datetime(end..start), end);
+    }
+
+    #[test]
+    fn next_f64_full_fills_low_mantissa_bits() {
+        let mut rng = WyRand::with_seed(0);
+        let mut counts = [0u32; 256];
+        let samples = 256_000;
+        for _ in 0..samples {
+            let x = rng.next_f64_full();
+            assert!((0.0..1.0).contains(&x));
+            let scaled = x * (1u64 << 52) as f64;
+            assert_eq!(scaled.fract(), 0.0, "not a multiple of 2^-52");
+            counts[(scaled as u64 & 255) as usize] += 1;
+        }
+        let expected = samples as f64 / 256.0;
+        let chi2: f64 = counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum();
+        // critical value for 255 degrees of freedom at p = 0.001
+        assert!(chi2 < 330.5, "low bits chi-squared {chi2}");
+    }
+
+    #[test]
+    fn bool_grid_size_and_fill() {
+        let mut rng = WyRand::with_seed(0);
+        let grid = rng.bool_grid(200, 150, 0.45);
+        assert_eq!(grid.len(), 200 * 150);
+        let fill = grid.iter().filter(|&&b| b).count() as f64 / grid.len() as f64;
+        assert!((fill - 0.45).abs() < 0.01, "fill {fill}");
+        assert!(rng.bool_grid(10, 10, 0.0).iter().all(|&b| !b));
+        assert!(rng.bool_grid(10, 10, 1.5).iter().all(|&b| b));
+    }
+
+    #[test]
+    fn next_in_range_ref_respects_bounds() {
+        let mut rng = WyRand::with_seed(0);
+        let mut same = WyRand::with_seed(0);
+        let ints = -7..13i32;
+        let floats = 0.25..0.75f64;
+        for _ in 0..10_000 {
+            let x = rng.next_in_range_ref(&ints);
+            assert!(ints.contains(&x));
+            assert_eq!(x, same.next_in_range(ints.clone()));
+            assert!(floats.contains(&rng.next_in_range_ref(&floats)));
+            same.next::<u64>();
+        }
+    }
+
+    #[test]
+    fn next_in_range_t_returns_its_lerp_parameter() {
+        let mut rng = WyRand::with_seed(0);
+        for _ in 0..10_000 {
+            let (value, t) = rng.next_in_range_t(-3.0..5.0);
+            assert!((0.0..1.0).contains(&t));
+            assert_eq!(value, -3.0 * (1.0 - t) + 5.0 * t);
+        }
+    }
+
+    #[test]
+    fn next_where_only_returns_matches() {
+        let mut rng = WyRand::with_seed(0);
+        for _ in 0..10_000 {
+            assert!(rng.next_where(|x: &u32| x.is_multiple_of(2)).is_multiple_of(2));
+        }
+    }
+
+    #[test]
+    fn shuffle_by_key_keeps_groups_contiguous() {
+        let mut rng = WyRand::with_seed(0);
+        for _ in 0..1_000 {
+            let mut items: Vec<(u8, u32)> = (0..30).map(|i| ((i % 4) as u8, i)).collect();
+            rng.shuffle_by_key(&mut items, |item| item.0);
+            // once a key's run ends, that key never appears again
+            let mut finished = Vec::new();
+            for w in items.windows(2) {
+                if w[0].0 != w[1].0 {
+                    finished.push(w[0].0);
+                    assert!(!finished.contains(&w[1].0));
+                }
+            }
+            let mut ids: Vec<u32> = items.iter().map(|item| item.1).collect();
+            ids.sort_unstable();
+            assert_eq!(ids, (0..30).collect::<Vec<_>>());
+        }
+
+        // with distinct keys every ordering is equally likely
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..60_000 {
+            let mut items = [0, 1, 2];
+            rng.shuffle_by_key(&mut items, |&x| x);
+            *counts.entry(items).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 6);
+        assert!(counts.values().all(|&c| (9_500..10_500).contains(&c)), "{counts:?}");
+    }
+
+    #[test]
+    fn gaussian_int_mean_and_spread() {
+        let mut rng = WyRand::with_seed(0);
+        let samples: Vec<f64> = (0..100_000).map(|_| rng.next_gaussian_int(10.0, 3.0) as f64).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!((mean - 10.0).abs() < 0.05, "mean {mean}");
+        // rounding adds the variance of a unit uniform, 1/12
+        assert!((var.sqrt() - (9.0f64 + 1.0 / 12.0).sqrt()).abs() < 0.05, "std dev {}", var.sqrt());
+    }
+
+    #[test]
+    fn pick_exactly_sets_m_trues() {
+        let mut rng = WyRand::with_seed(0);
+        let mut hits = [0u32; 10];
+        for m in 0..=10 {
+            let mask = rng.pick_exactly(10, m);
+            assert_eq!(mask.len(), 10);
+            assert_eq!(mask.iter().filter(|&&b| b).count(), m);
+        }
+        for _ in 0..10_000 {
+            for (i, &b) in rng.pick_exactly(10, 3).iter().enumerate() {
+                hits[i] += b as u32;
+            }
+        }
+        assert!(hits.iter().all(|&h| (2_800..3_200).contains(&h)), "{hits:?}");
+    }
+
+    #[test]
+    fn jitter_preserving_sum_keeps_the_total() {
+        let mut rng = WyRand::with_seed(0);
+        let mut values: Vec<f64> = (0..100).map(|i| i as f64 * 0.5).collect();
+        let original = values.clone();
+        let total: f64 = values.iter().sum();
+        rng.jitter_preserving_sum(&mut values, 0.25);
+        assert!((values.iter().sum::<f64>() - total).abs() < 1e-9);
+        assert_ne!(values, original);
+        assert!(values.iter().zip(&original).all(|(v, o)| (v - o).abs() <= 0.5));
+    }
+
+    #[test]
+    fn erdos_renyi_edge_count() {
+        let mut rng = WyRand::with_seed(0);
+        let n = 200;
+        let edges = rng.erdos_renyi(n, 0.1);
+        assert!(edges.iter().all(|&(i, j)| i < j && j < n));
+        let mut unique = edges.clone();
+        unique.dedup();
+        assert_eq!(unique.len(), edges.len());
+        let expected = 0.1 * (n * (n - 1) / 2) as f64;
+        assert!((edges.len() as f64 - expected).abs() < expected * 0.05, "{} edges", edges.len());
+    }
+
+    #[test]
+    fn simplex_weights_sum_to_one() {
+        let mut rng = WyRand::with_seed(0);
+        for n in 1..20 {
+            let weights = rng.next_simplex_weights(n);
+            assert_eq!(weights.len(), n);
+            assert!(weights.iter().all(|&w| w >= 0.0));
+            assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn rotations_are_orthonormal() {
+        let mut rng = WyRand::with_seed(0);
+        for _ in 0..1_000 {
+            let m = rng.next_rotation3();
+            assert!((m * m.transpose()).abs_diff_eq(Mat3::IDENTITY, 1e-5));
+            assert!((m.determinant() - 1.0).abs() < 1e-5);
+            let m = rng.next_rotation2();
+            assert!((m * m.transpose()).abs_diff_eq(Mat2::IDENTITY, 1e-5));
+            assert!((m.determinant() - 1.0).abs() < 1e-5);
+        }
+
+        let (mut a, mut b) = (WyRand::with_seed(0), WyRand::with_seed(0));
+        for _ in 0..100 {
+            assert_eq!(a.next_rotation3(), b.next_rotation3());
+            assert_eq!(a.next_rotation2(), b.next_rotation2());
+        }
+    }
+
+    #[test]
+    fn open_unit_excludes_both_ends() {
+        for v in [0, 1, 1 << 12, u64::MAX - (1 << 12), u64::MAX] {
+            let x = open_unit(v);
+            assert!(x > 0.0 && x < 1.0, "{v} mapped to {x}");
+            assert!(x.ln().is_finite() && (1.0 - x).ln().is_finite());
+        }
+        let mut rng = WyRand::with_seed(0);
+        assert!((0..10_000).all(|_| (0.0..1.0).contains(&rng.next_open_unit())));
+    }
+
+    #[test]
+    fn pareto_minimum_and_tail() {
+        let mut rng = WyRand::with_seed(0);
+        let samples: Vec<f64> = (0..100_000).map(|_| rng.next_pareto(2.0, 1.5)).collect();
+        assert!(samples.iter().all(|&x| x >= 2.0));
+        // P(X > x) = (scale / x)^shape
+        for x in [4.0, 20.0] {
+            let expected = (2.0f64 / x).powf(1.5);
+            let observed = samples.iter().filter(|&&s| s > x).count() as f64 / samples.len() as f64;
+            assert!((observed - expected).abs() < expected * 0.1, "P(X > {x}) = {observed}");
+        }
+    }
+
+    #[test]
+    fn next_runs_length_and_mean_run() {
+        let mut rng = WyRand::with_seed(0);
+        let values = rng.next_runs(100_000, 5.0);
+        assert_eq!(values.len(), 100_000);
+        let runs = 1 + values.windows(2).filter(|w| w[0] != w[1]).count();
+        let mean_run = values.len() as f64 / runs as f64;
+        assert!((mean_run - 5.0).abs() < 0.2, "mean run {mean_run}");
+        assert!(rng.next_runs(0, 5.0).is_empty());
+        assert_eq!(rng.next_runs(50, 0.5).windows(2).filter(|w| w[0] == w[1]).count(), 0);
+    }
+
+    #[test]
+    fn sample_cdf_matches_probabilities() {
+        let mut rng = WyRand::with_seed(0);
+        let cdf = [0.1, 0.1, 0.6, 1.0];
+        let mut counts = [0u32; 4];
+        for _ in 0..100_000 {
+            counts[rng.sample_cdf(&cdf)] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        for (count, p) in counts.iter().zip([0.1, 0.0, 0.5, 0.4]) {
+            assert!((*count as f64 / 100_000.0 - p).abs() < 0.01, "{counts:?}");
+        }
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn affine2_decomposes_into_its_ranges() {
+        let mut rng = WyRand::with_seed(0);
+        let translation = Vec2::new(-5.0, 1.0)..Vec2::new(5.0, 3.0);
+        for _ in 0..1_000 {
+            let t = rng.next_affine2(0.5..2.0, 0.0..1.5, translation.clone());
+            let (scale, angle, offset) = t.to_scale_angle_translation();
+            assert!((scale.x - scale.y).abs() < 1e-4, "uniform scale {scale}");
+            assert!((0.5 - 1e-4..2.0 + 1e-4).contains(&scale.x));
+            assert!((-1e-4..1.5 + 1e-4).contains(&angle));
+            assert!(offset.x >= -5.0 && offset.x < 5.0 && offset.y >= 1.0 && offset.y < 3.0);
+        }
+    }
+
+    #[test]
+    fn next_bits_stays_under_its_mask() {
+        let mut rng = WyRand::with_seed(0);
+        for bits in 0..=64 {
+            for _ in 0..100 {
+                let v = rng.next_bits(bits);
+                assert!(bits == 64 || v < 1 << bits, "{v} has more than {bits} bits");
+            }
+        }
+        let ones: u64 = (0..100_000).map(|_| rng.next_bits(1)).sum();
+        assert!((49_000..51_000).contains(&ones), "{ones} ones");
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn in_triangle_is_inside_and_area_uniform() {
+        let mut rng = WyRand::with_seed(0);
+        let mut regions = [0u32; 4];
+        for _ in 0..40_000 {
+            let p = rng.in_triangle(Vec2::ZERO, Vec2::X, Vec2::Y);
+            assert!(p.x >= -1e-6 && p.y >= -1e-6 && p.x + p.y <= 1.0 + 1e-6);
+            // the midpoints split the triangle into four of equal area
+            let region = if p.x >= 0.5 { 0 } else if p.y >= 0.5 { 1 } else if p.x + p.y < 0.5 { 2 } else { 3 };
+            regions[region] += 1;
+        }
+        assert!(regions.iter().all(|&r| (9_600..10_400).contains(&r)), "{regions:?}");
+
+        let (a, b, c) = (Vec3::new(1.0, 2.0, 3.0), Vec3::new(-4.0, 0.0, 1.0), Vec3::new(2.0, -1.0, 5.0));
+        let normal = (b - a).cross(c - a);
+        for _ in 0..1_000 {
+            let p = rng.in_triangle3(a, b, c);
+            assert!((p - a).dot(normal).abs() < 1e-3, "off the plane");
+        }
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn sample_mesh_is_on_the_mesh_and_area_weighted() {
+        let mut rng = WyRand::with_seed(0);
+        // a unit right triangle at z = 0, and one with 4x the area at z = 1
+        let vertices = [
+            Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0), Vec3::new(2.0, 0.0, 1.0), Vec3::new(0.0, 2.0, 1.0),
+        ];
+        let points = rng.sample_mesh(&vertices, &[0, 1, 2, 3, 4, 5], 50_000);
+        assert_eq!(points.len(), 50_000);
+        let mut on_small = 0;
+        for p in &points {
+            let small = p.z.abs() < 1e-5;
+            assert!(small || (p.z - 1.0).abs() < 1e-5, "{p} is off both triangles");
+            let limit = if small { 1.0 } else { 2.0 };
+            assert!(p.x >= -1e-5 && p.y >= -1e-5 && p.x + p.y <= limit + 1e-5);
+            on_small += small as u32;
+        }
+        assert!((9_500..10_500).contains(&on_small), "{on_small} on the small triangle");
+        assert!(rng.sample_mesh(&vertices, &[0, 0, 1], 10).is_empty());
+    }
+
+    #[test]
+    fn shuffle_range_leaves_the_rest_alone() {
+        let mut rng = WyRand::with_seed(0);
+        for _ in 0..1_000 {
+            let mut items: Vec<u32> = (0..20).collect();
+            rng.shuffle_range(&mut items, 5..15);
+            assert_eq!(&items[..5], &[0, 1, 2, 3, 4]);
+            assert_eq!(&items[15..], &[15, 16, 17, 18, 19]);
+            let mut middle = items[5..15].to_vec();
+            middle.sort_unstable();
+            assert_eq!(middle, (5..15).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn partition_places_every_item_once() {
+        let mut rng = WyRand::with_seed(0);
+        let items: Vec<u32> = (0..1_000).collect();
+        let groups = rng.partition(&items, 4);
+        assert_eq!(groups.len(), 4);
+        let mut all: Vec<u32> = groups.iter().flatten().copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, items);
+        assert!(groups.iter().all(|g| (200..300).contains(&g.len())));
+
+        // the assignment depends on the seed
+        assert_eq!(groups, WyRand::with_seed(0).partition(&items, 4));
+        assert_ne!(groups, WyRand::with_seed(1).partition(&items, 4));
+    }
+
+    #[test]
+    fn split_balanced_sizes_and_items() {
+        let mut rng = WyRand::with_seed(0);
+        let mut items: Vec<u32> = (0..23).collect();
+        let groups = rng.split_balanced(&mut items, 5);
+        assert!(items.is_empty());
+        let sizes: Vec<usize> = groups.iter().map(Vec::len).collect();
+        assert_eq!(sizes.iter().sum::<usize>(), 23);
+        assert!(sizes.iter().max().unwrap() - sizes.iter().min().unwrap() <= 1);
+        let mut all: Vec<u32> = groups.into_iter().flatten().collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..23).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shaped_range_biases_toward_start() {
+        let mut rng = WyRand::with_seed(0);
+        let samples: Vec<f64> = (0..100_000).map(|_| rng.next_in_range_shaped(0.0..1.0, |t| t * t)).collect();
+        assert!(samples.iter().all(|x| (0.0..1.0).contains(x)));
+        // for t * t, P(x < 0.25) = P(t < 0.5) = 0.5 and the mean is 1/3
+        let below = samples.iter().filter(|&&x| x < 0.25).count() as f64 / samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert!((below - 0.5).abs() < 0.01, "{below}");
+        assert!((mean - 1.0 / 3.0).abs() < 0.01, "{mean}");
+    }
+
+    #[test]
+    fn choose_static_follows_weight_ratios() {
+        let mut rng = WyRand::with_seed(0);
+        let mut counts = [0u32; 4];
+        for _ in 0..80_000 {
+            counts[rng.choose_static(&[0, 1, 2, 3], &[1, 0, 3, 4])] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!((9_500..10_500).contains(&counts[0]), "{counts:?}");
+        assert!((29_000..31_000).contains(&counts[2]), "{counts:?}");
+        assert!((39_000..41_000).contains(&counts[3]), "{counts:?}");
+    }
+
+    #[test]
+    fn correlated_bools_rate_and_correlation() {
+        let mut rng = WyRand::with_seed(0);
+        let values: Vec<f64> = rng.correlated_bools(200_000, 0.3, 0.6).into_iter().map(|b| b as u8 as f64).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        assert!((mean - 0.3).abs() < 0.01, "rate {mean}");
+        let var = mean * (1.0 - mean);
+        let cov = values.windows(2).map(|w| (w[0] - mean) * (w[1] - mean)).sum::<f64>() / (values.len() - 1) as f64;
+        assert!((cov / var - 0.6).abs() < 0.02, "correlation {}", cov / var);
+    }
+
+    #[test]
+    fn next_f32_in_range_is_half_open_and_reproducible() {
+        let mut a = WyRand::with_seed(0);
+        let mut b = WyRand::with_seed(0);
+        for range in [1.0..2.0f32, -1e30..1e30, f32::MIN..f32::MAX, 0.0..1e-30] {
+            for _ in 0..10_000 {
+                let x = a.next_f32_in_range(range.clone());
+                assert!(x >= range.start && x < range.end, "{x} outside {range:?}");
+                assert_eq!(x, b.next_f32_in_range(range.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn permutation_array_is_valid() {
+        let mut rng = WyRand::with_seed(0);
+        let mut table: [u8; 256] = rng.permutation_array();
+        table.sort_unstable();
+        assert!(table.iter().enumerate().all(|(i, &b)| b as usize == i));
+        let mut small: [u8; 5] = rng.permutation_array();
+        small.sort_unstable();
+        assert_eq!(small, [0, 1, 2, 3, 4]);
+        let _: [u8; 0] = rng.permutation_array();
+
+        let a: [u8; 16] = WyRand::with_seed(0).permutation_array();
+        let b: [u8; 16] = WyRand::with_seed(1).permutation_array();
+        assert_eq!(a, WyRand::with_seed(0).permutation_array());
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn palette_hues_are_spread() {
+        let palette = WyRand::with_seed(0).next_palette(8);
+        assert_eq!(palette.len(), 8);
+        assert_eq!(palette, WyRand::with_seed(0).next_palette(8));
+
+        let hue = |[r, g, b]: [u8; 3]| {
+            let (r, g, b) = (r as f64, g as f64, b as f64);
+            let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+            let h = if max == r {
+                (g - b) / (max - min)
+            } else if max == g {
+                2.0 + (b - r) / (max - min)
+            } else {
+                4.0 + (r - g) / (max - min)
+            };
+            (h / 6.0).rem_euclid(1.0)
+        };
+        let mut hues: Vec<f64> = palette.into_iter().map(hue).collect();
+        hues.sort_by(f64::total_cmp);
+        let gaps: Vec<f64> = hues.windows(2).map(|w| w[1] - w[0]).chain([hues[0] + 1.0 - hues[7]]).collect();
+        // golden ratio steps leave no gap much wider or narrower than 1 / 8
+        assert!(gaps.iter().all(|&g| g > 0.05 && g < 0.25), "{gaps:?}");
+    }
+
+    #[test]
+    fn sattolo_leaves_no_fixed_points() {
+        let mut rng = WyRand::with_seed(0);
+        for len in 2..20 {
+            for _ in 0..50 {
+                let mut v: Vec<usize> = (0..len).collect();
+                rng.shuffle_with(&mut v, ShuffleAlgo::Sattolo);
+                assert!(v.iter().enumerate().all(|(i, &x)| i != x), "{v:?}");
+            }
+        }
+
+        // a plain shuffle can leave elements in place
+        let has_fixed_point = |v: &[usize]| v.iter().enumerate().any(|(i, &x)| i == x);
+        assert!((0..50).any(|_| {
+            let mut v: Vec<usize> = (0..5).collect();
+            rng.shuffle(&mut v);
+            has_fixed_point(&v)
+        }));
+        assert!((0..50).any(|_| {
+            let mut v: Vec<usize> = (0..5).collect();
+            rng.shuffle_with(&mut v, ShuffleAlgo::FisherYates);
+            has_fixed_point(&v)
+        }));
+    }
+
+    #[test]
+    fn derangement_is_uniform() {
+        let mut rng = WyRand::with_seed(0);
+        assert_eq!(rng.derangement(1), None);
+        assert_eq!(rng.derangement(0), Some(vec![]));
+        assert_eq!(rng.derangement(2), Some(vec![1, 0]));
+
+        // 4 elements have 9 derangements, each should come up about equally
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..9000 {
+            let d = rng.derangement(4).unwrap();
+            assert!(d.iter().enumerate().all(|(i, &x)| i != x), "{d:?}");
+            *counts.entry(d).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 9);
+        assert!(counts.values().all(|&c| (850..1150).contains(&c)), "{counts:?}");
+    }
+
+    #[test]
+    fn assign_cyclic_forms_one_ring() {
+        let mut rng = WyRand::with_seed(0);
+        let people: Vec<usize> = (0..10).collect();
+        for _ in 0..50 {
+            let pairs = rng.assign_cyclic(&people);
+            assert!(pairs.iter().enumerate().all(|(i, (g, _))| **g == i));
+            // following the receivers from anyone visits everyone once
+            let mut seen = vec![false; people.len()];
+            let mut at = 0;
+            for _ in 0..people.len() {
+                assert!(!seen[at]);
+                seen[at] = true;
+                at = *pairs[at].1;
+            }
+            assert_eq!(at, 0);
+        }
+        assert_eq!(rng.assign_cyclic(&["solo"]), vec![(&"solo", &"solo")]);
+    }
+
+    #[test]
+    fn accept_follows_temperature() {
+        let mut rng = WyRand::with_seed(0);
+        assert!((0..100).all(|_| rng.accept(-1.0, 0.0) && rng.accept(0.0, 1.0)));
+        assert!((0..100).all(|_| !rng.accept(1.0, 0.0)));
+
+        let rate = |rng: &mut WyRand, t: f64| (0..10_000).filter(|_| rng.accept(1.0, t)).count() as f64 / 10_000.0;
+        let (cold, hot) = (rate(&mut rng, 0.5), rate(&mut rng, 5.0));
+        assert!((cold - (-2.0f64).exp()).abs() < 0.02, "{cold}");
+        assert!((hot - (-0.2f64).exp()).abs() < 0.02, "{hot}");
+    }
+
+    #[test]
+    fn index_excluding_is_uniform_over_the_rest() {
+        let mut rng = WyRand::with_seed(0);
+        let excluded = [0, 3, 3, 7, 42];
+        let mut counts = [0u32; 8];
+        for _ in 0..50_000 {
+            counts[rng.index_excluding(8, &excluded).unwrap()] += 1;
+        }
+        for (i, &c) in counts.iter().enumerate() {
+            if excluded.contains(&i) {
+                assert_eq!(c, 0);
+            } else {
+                assert!((9_500..10_500).contains(&c), "{counts:?}");
+            }
+        }
+        assert_eq!(rng.index_excluding(3, &[0, 1, 2]), None);
+        assert_eq!(rng.index_excluding(0, &[]), None);
+    }
+
+    #[test]
+    fn sort_key_ignores_input_order() {
+        let rng = WyRand::with_seed(0);
+        let mut forward: Vec<u64> = (0..100).collect();
+        let mut backward: Vec<u64> = (0..100).rev().collect();
+        forward.sort_by_key(|&id| rng.sort_key(id));
+        backward.sort_by_key(|&id| rng.sort_key(id));
+        assert_eq!(forward, backward);
+        assert_ne!(forward, (0..100).collect::<Vec<u64>>());
+        assert_eq!(rng.peek::<u64>(), WyRand::with_seed(0).peek::<u64>());
+        assert_ne!(rng.sort_key(5), WyRand::with_seed(1).sort_key(5));
+    }
+
+    #[test]
+    fn random_utf8_has_exact_length() {
+        let mut rng = WyRand::with_seed(0);
+        let mut widths = [0u32; 5];
+        for len in 0..200 {
+            let s = rng.random_utf8(len);
+            assert_eq!(s.len(), len);
+            assert!(std::str::from_utf8(s.as_bytes()).is_ok());
+            s.chars().for_each(|c| widths[c.len_utf8()] += 1);
+        }
+        // every encoded width shows up
+        assert!(widths[1..].iter().all(|&w| w > 100), "{widths:?}");
+    }
+
+    #[test]
+    fn peek_does_not_advance() {
+        let mut rng = WyRand::with_seed(0);
+        for _ in 0..10 {
+            let (peeked, draws) = (rng.peek::<u64>(), rng.draws_since_seed());
+            assert_eq!(rng.peek::<u64>(), peeked);
+            assert_eq!(rng.draws_since_seed(), draws);
+            assert_eq!(rng.next::<u64>(), peeked);
+        }
+        assert_eq!(rng.draws_since_seed(), 10);
+    }
+
+    #[test]
+    #[cfg(feature = "glam")]
+    fn fill_directions3_gives_unit_vectors() {
+        let mut out = vec![Vec3::ZERO; 10_000];
+        WyRand::with_seed(0).fill_directions3(&mut out);
+        assert!(out.iter().all(|v| (v.length() - 1.0).abs() < 1e-5));
+        let mean = out.iter().copied().sum::<Vec3>() / out.len() as f32;
+        assert!(mean.length() < 0.05, "{mean}");
+
+        let mut again = vec![Vec3::ZERO; 10_000];
+        WyRand::with_seed(0).fill_directions3(&mut again);
+        assert_eq!(out, again);
+    }
+
+    #[test]
+    fn wrapping_range_near_type_bounds() {
+        let mut rng = WyRand::with_seed(0);
+        let (mut low, mut high) = (false, false);
+        for _ in 0..1000 {
+            let v = rng.next_in_wrapping_range(250u8, 10, BandMode::Saturate);
+            assert!(v >= 240);
+            let v = rng.next_in_wrapping_range(-125i8, 10, BandMode::Saturate);
+            assert!(v <= -115);
+
+            // 250 +- 10 wraps to 240..=255 and 0..=4
+            let v = rng.next_in_wrapping_range(250u8, 10, BandMode::Wrap);
+            assert!(v >= 240 || v <= 4, "{v}");
+            low |= v <= 4;
+            high |= v >= 240;
+        }
+        assert!(low && high);
+
+        // clipping at the bound leaves the band uniform over MAX - 7..=MAX
+        let mut counts = [0u32; 8];
+        for _ in 0..80_000 {
+            counts[(rng.next_in_wrapping_range(u64::MAX - 2, 5, BandMode::Saturate) - (u64::MAX - 7)) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| (9_400..10_600).contains(&c)), "{counts:?}");
+
+        let _ = rng.next_in_wrapping_range(0i64, i64::MAX, BandMode::Wrap);
+        assert_eq!(rng.next_in_wrapping_range(i32::MIN, 0, BandMode::Wrap), i32::MIN);
+    }
+
+    #[test]
+    fn symmetric_bool_matrix_is_an_undirected_graph() {
+        let n = 100;
+        let m = WyRand::with_seed(0).symmetric_bool_matrix(n, 0.3);
+        assert_eq!(m.len(), n * n);
+        for i in 0..n {
+            assert!(!m[i * n + i]);
+            for j in 0..n {
+                assert_eq!(m[i * n + j], m[j * n + i]);
+            }
+        }
+        let density = m.iter().filter(|&&e| e).count() as f64 / (n * (n - 1)) as f64;
+        assert!((density - 0.3).abs() < 0.02, "{density}");
+
+        assert!(WyRand::with_seed(0).symmetric_bool_matrix(10, 0.0).iter().all(|&e| !e));
+        assert_eq!(WyRand::with_seed(0).symmetric_bool_matrix(10, 1.0).iter().filter(|&&e| e).count(), 90);
+    }
+
+    #[test]
+    fn u_shaped_histogram() {
+        let mut rng = WyRand::with_seed(0);
+        let mut bins = [0u32; 10];
+        for _ in 0..100_000 {
+            let x = rng.next_u_shaped(-1.0..1.0);
+            assert!((-1.0..=1.0).contains(&x));
+            bins[(((x + 1.0) * 5.0) as usize).min(9)] += 1;
+        }
+        let cdf = |x: f64| std::f64::consts::FRAC_2_PI * x.sqrt().asin();
+        for (i, &count) in bins.iter().enumerate() {
+            let expected = (cdf((i + 1) as f64 / 10.0) - cdf(i as f64 / 10.0)) * 100_000.0;
+            assert!((count as f64 - expected).abs() < expected * 0.05, "{bins:?}");
+        }
+        assert!(bins[0] > 2 * bins[4] && bins[9] > 2 * bins[5]);
+    }
+
+    #[test]
+    fn permutation_cycles_partition_uniformly() {
+        let mut rng = WyRand::with_seed(0);
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..24_000 {
+            let cycles = rng.random_permutation_cycles(4);
+            let mut elements: Vec<usize> = cycles.concat();
+            elements.sort_unstable();
+            assert_eq!(elements, [0, 1, 2, 3]);
+            assert!(cycles.iter().all(|c| c[0] == *c.iter().min().unwrap()));
+            assert!(cycles.windows(2).all(|w| w[0][0] < w[1][0]));
+
+            // rebuild the permutation the cycles describe
+            let mut perm = [0; 4];
+            for c in &cycles {
+                for (k, &i) in c.iter().enumerate() {
+                    perm[i] = c[(k + 1) % c.len()];
+                }
+            }
+            *counts.entry(perm).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 24);
+        assert!(counts.values().all(|&c| (850..1150).contains(&c)), "{counts:?}");
+        assert!(rng.random_permutation_cycles(0).is_empty());
+    }
+
+    #[test]
+    fn spawn_child_diverges_reproducibly() {
+        let mut parent = WyRand::with_seed(0);
+        parent.set_rejection_cap(7);
+        let mut child = parent.spawn_child();
+        assert_eq!(parent.draws_since_seed(), 1);
+        assert_eq!(child.rejection_cap(), 7);
+        assert_eq!(child.draws_since_seed(), 0);
+
+        let child_values: Vec<u64> = (0..100).map(|_| child.next()).collect();
+        let parent_values: Vec<u64> = (0..100).map(|_| parent.next()).collect();
+        assert!(child_values.iter().all(|v| !parent_values.contains(v)));
+
+        let mut again = WyRand::with_seed(0).spawn_child();
+        assert_eq!(child_values, (0..100).map(|_| again.next()).collect::<Vec<u64>>());
+        let mut sibling = WyRand::with_seed(0);
+        sibling.spawn_child();
+        assert_ne!(sibling.spawn_child().next::<u64>(), child_values[0]);
+    }
+
+    #[test]
+    fn weighted_tilemap_matches_weights() {
+        let tiles = [(b'.', 6.0), (b'#', 3.0), (b'~', 1.0), (b'!', 0.0)];
+        let map = WyRand::with_seed(0).weighted_tilemap(200, 300, &tiles);
+        assert_eq!(map.len(), 200 * 300);
+        assert_eq!(map, WyRand::with_seed(0).weighted_tilemap(200, 300, &tiles));
+        for (tile, weight) in tiles {
+            let share = map.iter().filter(|&&t| t == tile).count() as f64 / map.len() as f64;
+            assert!((share - weight / 10.0).abs() < 0.01, "{} {share}", tile as char);
+        }
+        assert!(WyRand::with_seed(0).weighted_tilemap(0, 5, &tiles).is_empty());
+    }
+
+    #[test]
+    fn reseed_resets_draw_count() {
+        let mut rng = WyRand::with_seed(0);
+        assert_eq!(rng.draws_since_seed(), 0);
+        let _ = rng.next::<u64>();
+        let _ = rng.next::<f64>();
+        let _ = rng.peek::<u64>();
+        assert_eq!(rng.draws_since_seed(), 2);
+
+        rng.set_rejection_cap(3);
+        rng.reseed(42);
+        assert_eq!(rng.draws_since_seed(), 0);
+        assert_eq!(rng.rejection_cap(), 3);
+        assert_eq!(rng.next::<u64>(), WyRand::with_seed(42).next::<u64>());
+        assert_eq!(rng.draws_since_seed(), 1);
+    }
+
+    #[test]
+    fn choose_by_weight_follows_weights() {
+        let mut rng = WyRand::with_seed(0);
+        let items = ["common", "rare", "never"];
+        let weight = |s: &&str| match *s {
+            "common" => 4.0,
+            "rare" => 1.0,
+            _ => 0.0,
+        };
+        let rare = (0..50_000)
+            .filter(|_| match *rng.choose_by_weight(&items, weight).unwrap() {
+                "never" => panic!("chose a zero-weight item"),
+                s => s == "rare",
+            })
+            .count();
+        assert!((rare as f64 / 50_000.0 - 0.2).abs() < 0.01, "{rare}");
+
+        assert_eq!(rng.choose_by_weight(&items, |_| 0.0), None);
+        assert_eq!(rng.choose_by_weight(&[] as &[&str], weight), None);
+    }
+
+    #[test]
+    fn empty_or_reversed_float_ranges_return_start() {
+        let mut rng = WyRand::with_seed(0);
+        for _ in 0..100 {
+            assert_eq!(rng.next_in_range(2.0..1.0f64), 2.0);
+            assert_eq!(rng.next_in_range(1.0..1.0f64), 1.0);
+            assert_eq!(rng.next_in_range(2.0..1.0f32), 2.0);
+        }
+    }
+}
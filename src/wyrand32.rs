@@ -0,0 +1,205 @@
+use std::ops::Range;
+
+/// A WyRand-style RNG with 32-bit state and 32-bit mixing.
+///
+/// 64-bit multiplies are slow on wasm32. WyRand32 only does 32-bit
+/// arithmetic, for when u32-quality randomness is enough and throughput
+/// matters. It generates 32-bit-and-smaller types.
+#[derive(Copy, Clone)]
+pub struct WyRand32 {
+    /// The current value of the RNG.
+    state: u32,
+}
+
+impl WyRand32 {
+    /// Construct a new WyRand32 instance.
+    pub fn new() -> Self {
+        Self::with_local_seed()
+    }
+
+    /// Construct a new WyRand32 instance with your own seed.
+    pub fn with_seed(seed: u32) -> Self {
+        Self { state: seed }
+    }
+
+    /// Construct the WyRand32 instance with a seed generated from the
+    /// thread-local WyRand seed generator.
+    pub fn with_local_seed() -> Self {
+        Self::with_seed((crate::seed::from_local() >> 32) as u32)
+    }
+
+    /// Construct a WyRand32 instance from system source.
+    /// 
+    /// This IS a system call on x86 - shouldn't be used frequently.
+    pub fn with_system_seed() -> Self {
+        Self::with_seed((crate::seed::from_system() >> 32) as u32)
+    }
+
+    /// Generate a value by updating and hashing the state.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next<T: FromRng32>(&mut self) -> T {
+        self.state = self.state.wrapping_add(0x9E37_79B9);
+        // xorshift-multiply finalizer
+        let mut z = self.state;
+        z = (z ^ (z >> 16)).wrapping_mul(0x7FEB_352D);
+        z = (z ^ (z >> 15)).wrapping_mul(0x846C_A68B);
+        T::from_rng32(z ^ (z >> 16))
+    }
+
+    /// Generate a value by updating and hashing the state, then wrapping to the range.
+    pub fn next_in_range<T: RangeRng32>(&mut self, range: Range<T>) -> T {
+        T::from_range32(self.next(), range)
+    }
+
+    /// Shuffle a slice
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            slice.swap(i, self.next_in_range(0..i as u32 + 1) as usize)
+        }
+    }
+}
+
+impl Default for WyRand32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub trait FromRng32 {
+    fn from_rng32(v: u32) -> Self;
+}
+
+impl FromRng32 for u32 {
+    fn from_rng32(v: u32) -> Self {
+        v
+    }
+}
+
+impl FromRng32 for i32 {
+    fn from_rng32(v: u32) -> Self {
+        v as i32
+    }
+}
+
+impl FromRng32 for u16 {
+    fn from_rng32(v: u32) -> Self {
+        (v >> 16) as u16
+    }
+}
+
+impl FromRng32 for i16 {
+    fn from_rng32(v: u32) -> Self {
+        (v >> 16) as i16
+    }
+}
+
+impl FromRng32 for u8 {
+    fn from_rng32(v: u32) -> Self {
+        (v >> 24) as u8
+    }
+}
+
+impl FromRng32 for i8 {
+    fn from_rng32(v: u32) -> Self {
+        (v >> 24) as i8
+    }
+}
+
+impl FromRng32 for bool {
+    fn from_rng32(v: u32) -> Self {
+        (v >> 31) == 1
+    }
+}
+
+impl FromRng32 for f32 {
+    fn from_rng32(v: u32) -> Self {
+        // 24 bits fill the f32 mantissa, in [0, 1)
+        (v >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
+
+pub trait RangeRng32: Sized {
+    fn from_range32(v: u32, range: Range<Self>) -> Self;
+}
+
+impl RangeRng32 for u32 {
+    fn from_range32(v: u32, range: Range<Self>) -> Self {
+        range.start + v % (range.end - range.start)
+    }
+}
+
+impl RangeRng32 for i32 {
+    fn from_range32(v: u32, range: Range<Self>) -> Self {
+        range.start.wrapping_add((v % range.end.wrapping_sub(range.start) as u32) as i32)
+    }
+}
+
+impl RangeRng32 for u16 {
+    fn from_range32(v: u32, range: Range<Self>) -> Self {
+        range.start.wrapping_add((v % (range.end as i32 - range.start as i32) as u32) as u16)
+    }
+}
+
+impl RangeRng32 for i16 {
+    fn from_range32(v: u32, range: Range<Self>) -> Self {
+        range.start.wrapping_add((v % (range.end as i32 - range.start as i32) as u32) as i16)
+    }
+}
+
+impl RangeRng32 for u8 {
+    fn from_range32(v: u32, range: Range<Self>) -> Self {
+        range.start.wrapping_add((v % (range.end as i32 - range.start as i32) as u32) as u8)
+    }
+}
+
+impl RangeRng32 for i8 {
+    fn from_range32(v: u32, range: Range<Self>) -> Self {
+        range.start.wrapping_add((v % (range.end as i32 - range.start as i32) as u32) as i8)
+    }
+}
+
+impl RangeRng32 for f32 {
+    fn from_range32(v: u32, range: Range<Self>) -> Self {
+        let u = f32::from_rng32(v);
+        // the lerp can round up to `end`, keep it exclusive.
+        (range.start * (1.0 - u) + range.end * u).min(range.end.next_down()).max(range.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wyrand32_is_deterministic_and_uniform() {
+        let mut a = WyRand32::with_seed(0);
+        let mut b = WyRand32::with_seed(0);
+        for _ in 0..1_000 {
+            assert_eq!(a.next::<u32>(), b.next::<u32>());
+        }
+
+        let mut counts = [0u32; 16];
+        for _ in 0..160_000 {
+            counts[a.next_in_range(0..16u32) as usize] += 1;
+        }
+        let chi2: f64 = counts.iter().map(|&c| (c as f64 - 10_000.0).powi(2) / 10_000.0).sum();
+        // critical value for 15 degrees of freedom at p = 0.001
+        assert!(chi2 < 37.7, "chi-squared {chi2}");
+
+        let mean = (0..100_000).map(|_| a.next::<f32>() as f64).sum::<f64>() / 100_000.0;
+        assert!((mean - 0.5).abs() < 0.005, "mean {mean}");
+    }
+
+    #[test]
+    fn f32_range_excludes_end() {
+        for range in [1.0..2.0f32, 100.0..101.0, -1.0..1.0, 0.0..1e-30] {
+            let max = f32::from_range32(u32::MAX, range.clone());
+            assert!(max >= range.start && max < range.end, "{max} outside {range:?}");
+        }
+        let mut rng = WyRand32::with_seed(0);
+        for _ in 0..100_000 {
+            let x = rng.next_in_range(100.0..101.0f32);
+            assert!((100.0..101.0).contains(&x), "{x}");
+        }
+    }
+}